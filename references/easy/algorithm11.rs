@@ -0,0 +1,44 @@
+// 参考实现：find_missing_number
+// 输入为 1..=n 去掉一个数后打乱的数组；偶尔生成较大的 n，以检查求和溢出
+use super::find_missing_number;
+
+pub struct Case;
+
+impl Differential for Case {
+    type Input = Vec<i32>;
+    type Output = i32;
+
+    fn generate(rng: &mut Rng, size: usize) -> Vec<i32> {
+        let n = if rng.one_in(50) { rng.range(40_000, 70_000) } else { rng.range(1, size as i64 + 1) };
+        let missing = rng.range(1, n);
+        let mut nums: Vec<i32> = (1..=n).filter(|&x| x != missing).map(|x| x as i32).collect();
+        rng.shuffle(&mut nums);
+        nums
+    }
+
+    fn reference(nums: &Vec<i32>) -> i32 {
+        let n = nums.len() as i64 + 1;
+        let sum: i64 = nums.iter().map(|&x| x as i64).sum();
+        (n * (n + 1) / 2 - sum) as i32
+    }
+
+    fn student(nums: &Vec<i32>) -> i32 {
+        find_missing_number(nums.clone())
+    }
+
+    // 去掉数组中最大的若干个数（缺失的数不在其中时），保持输入合法
+    fn shrink(nums: &Vec<i32>) -> Vec<Vec<i32>> {
+        let n = nums.len() as i32 + 1;
+        let missing = Self::reference(nums);
+        let mut candidates = Vec::new();
+        let mut removed = n / 2;
+        while removed > 0 {
+            let top = n - removed;
+            if missing <= top && top >= 1 {
+                candidates.push(nums.iter().cloned().filter(|&x| x <= top).collect());
+            }
+            removed /= 2;
+        }
+        candidates
+    }
+}
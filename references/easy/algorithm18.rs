@@ -0,0 +1,42 @@
+// 参考实现：merge_intervals
+use super::merge_intervals;
+
+pub struct Case;
+
+impl Differential for Case {
+    type Input = Vec<Vec<i32>>;
+    type Output = Vec<Vec<i32>>;
+
+    fn generate(rng: &mut Rng, size: usize) -> Vec<Vec<i32>> {
+        let count = rng.range(0, size as i64) as usize;
+        let limit = 2 * size as i64 + 10;
+        (0..count)
+            .map(|_| {
+                let start = rng.range(-limit, limit);
+                let end = start + rng.range(0, 10);
+                vec![start as i32, end as i32]
+            })
+            .collect()
+    }
+
+    fn reference(intervals: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
+        let mut sorted = intervals.clone();
+        sorted.sort();
+        let mut merged: Vec<Vec<i32>> = Vec::new();
+        for interval in sorted {
+            match merged.last_mut() {
+                Some(last) if interval[0] <= last[1] => last[1] = last[1].max(interval[1]),
+                _ => merged.push(interval),
+            }
+        }
+        merged
+    }
+
+    fn student(intervals: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
+        merge_intervals(intervals.clone())
+    }
+
+    fn shrink(intervals: &Vec<Vec<i32>>) -> Vec<Vec<Vec<i32>>> {
+        shrink_vec(intervals)
+    }
+}
@@ -0,0 +1,59 @@
+// 参考实现：find_max_prime_factor
+// 输入为若干随机小素数与一个不超过 10^6 的素数之积，试除即可
+mod prime_factor;
+
+use prime_factor::find_max_prime_factor;
+
+pub struct Case;
+
+const SMALL_PRIMES: [u64; 10] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+impl Differential for Case {
+    type Input = u64;
+    type Output = u128;
+
+    fn generate(rng: &mut Rng, size: usize) -> u64 {
+        let mut number = 1u64;
+        for _ in 0..rng.range(1, 1 + size as i64 / 8) {
+            number *= SMALL_PRIMES[rng.below(SMALL_PRIMES.len() as u64) as usize];
+        }
+        if rng.one_in(2) {
+            let mut large = rng.range(2, 1_000_000) as u64;
+            while !is_prime(large) {
+                large += 1;
+            }
+            number *= large;
+        }
+        number.max(2)
+    }
+
+    fn reference(&number: &u64) -> u128 {
+        let mut n = number;
+        let mut largest = 1;
+        let mut d = 2;
+        while d * d <= n {
+            while n % d == 0 {
+                largest = d;
+                n /= d;
+            }
+            d += 1;
+        }
+        if n > 1 {
+            largest = n;
+        }
+        largest as u128
+    }
+
+    fn student(&number: &u64) -> u128 {
+        find_max_prime_factor(number as u128)
+    }
+
+    // 只保留仍大于 1 的候选
+    fn shrink(&number: &u64) -> Vec<u64> {
+        shrink_int(number as i64).into_iter().filter(|&n| n >= 2).map(|n| n as u64).collect()
+    }
+}
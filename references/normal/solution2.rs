@@ -0,0 +1,45 @@
+// 参考实现：convert_base
+// 输入为 `数字(源进制)` 与目标进制，进制在 2~16 之间，数值不超过 u32
+mod converter;
+
+use converter::convert_base;
+
+pub struct Case;
+
+const DIGITS: &[u8] = b"0123456789abcdef";
+
+fn to_base(mut value: u64, base: u64) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(value % base) as usize]);
+        value /= base;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+impl Differential for Case {
+    // (数值, 源进制, 目标进制)
+    type Input = (u32, u32, u32);
+    type Output = String;
+
+    fn generate(rng: &mut Rng, size: usize) -> (u32, u32, u32) {
+        let value = if rng.one_in(20) { u32::MAX - rng.below(1000) as u32 } else { rng.below(1u64 << (size / 2).min(32)) as u32 };
+        (value, rng.range(2, 16) as u32, rng.range(2, 16) as u32)
+    }
+
+    fn reference(&(value, _, to): &(u32, u32, u32)) -> String {
+        to_base(value as u64, to as u64)
+    }
+
+    fn student(&(value, from, to): &(u32, u32, u32)) -> String {
+        convert_base(&format!("{}({})", to_base(value as u64, from as u64), from), to)
+    }
+
+    fn shrink(&(value, from, to): &(u32, u32, u32)) -> Vec<(u32, u32, u32)> {
+        shrink_int(value as i64).into_iter().map(|value| (value as u32, from, to)).collect()
+    }
+}
@@ -0,0 +1,34 @@
+// 参考实现：dp_rec_mc
+// 面值 1、2、5、10、20、50、100 构成规范硬币系统，贪心即为最优解
+mod rec_mc;
+
+use rec_mc::dp_rec_mc;
+
+pub struct Case;
+
+impl Differential for Case {
+    type Input = u32;
+    type Output = u32;
+
+    fn generate(rng: &mut Rng, size: usize) -> u32 {
+        rng.below(50 * size as u64 + 1) as u32
+    }
+
+    fn reference(&amount: &u32) -> u32 {
+        let mut remaining = amount;
+        let mut coins = 0;
+        for coin in [100, 50, 20, 10, 5, 2, 1] {
+            coins += remaining / coin;
+            remaining %= coin;
+        }
+        coins
+    }
+
+    fn student(&amount: &u32) -> u32 {
+        dp_rec_mc(amount)
+    }
+
+    fn shrink(&amount: &u32) -> Vec<u32> {
+        shrink_int(amount as i64).into_iter().map(|amount| amount as u32).collect()
+    }
+}
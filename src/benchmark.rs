@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+// 性能评测阶段在日志中的名称
+pub const BENCHMARK_BUILD_PHASE: &str = "benchmark build";
+pub const BENCHMARK_PHASE: &str = "benchmark";
+
+pub const DEFAULT_RUNS: usize = 5;
+pub const DEFAULT_TOLERANCE: f64 = 0.1;
+
+// 配置中的 `benchmark`：以 release 模式多次运行测试（或 bench）目标，中位数不超过 budget_ms * (1 + tolerance) 即达标
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BenchmarkSpec {
+    pub budget_ms: u64,
+    #[serde(default = "default_runs")]
+    pub runs: usize,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    // cargo 项目中计时的 `[[test]]` 或 `[[bench]]` 目标，都省略时运行全部测试目标
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench: Option<String>,
+}
+
+fn default_runs() -> usize {
+    DEFAULT_RUNS
+}
+
+fn default_tolerance() -> f64 {
+    DEFAULT_TOLERANCE
+}
+
+impl BenchmarkSpec {
+    // 以 release 模式构建计时目标的 cargo 参数（bench 本身即使用 release 配置），之后还需加上 `--no-run`
+    pub fn cargo_args(&self) -> Vec<&str> {
+        match (&self.test, &self.bench) {
+            (Some(test), _) => vec!["test", "--release", "--test", test],
+            (None, Some(bench)) => vec!["bench", "--bench", bench],
+            (None, None) => vec!["test", "--release", "--tests"],
+        }
+    }
+
+    // 判定用的上限（毫秒）
+    pub fn limit_ms(&self) -> f64 {
+        self.budget_ms as f64 * (1.0 + self.tolerance)
+    }
+}
+
+// 报告中的性能评测结果，时间单位为毫秒
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BenchmarkResult {
+    pub budget_ms: u64,
+    pub tolerance: f64,
+    pub runs: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+    pub within_budget: bool,
+}
+
+impl BenchmarkResult {
+    pub fn from_samples(spec: &BenchmarkSpec, samples: &[Duration]) -> Self {
+        let mut ms: Vec<f64> = samples.iter().map(|sample| sample.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let median = match ms.len() {
+            0 => 0.0,
+            n if n % 2 == 1 => ms[n / 2],
+            n => (ms[n / 2 - 1] + ms[n / 2]) / 2.0,
+        };
+        let round = |value: f64| (value * 100.0).round() / 100.0;
+        BenchmarkResult {
+            budget_ms: spec.budget_ms,
+            tolerance: spec.tolerance,
+            runs: ms.len(),
+            min_ms: round(ms.first().copied().unwrap_or_default()),
+            median_ms: round(median),
+            max_ms: round(ms.last().copied().unwrap_or_default()),
+            within_budget: median <= spec.limit_ms(),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Benchmark ({} runs, release): min {:.2} ms, median {:.2} ms, max {:.2} ms; budget {} ms (+{:.0}%)",
+            self.runs,
+            self.min_ms,
+            self.median_ms,
+            self.max_ms,
+            self.budget_ms,
+            self.tolerance * 100.0
+        );
+        if !self.within_budget && self.budget_ms > 0 {
+            let over = (self.median_ms / self.budget_ms as f64 - 1.0) * 100.0;
+            summary.push_str(&format!(", median is {:.0}% over the budget", over));
+        }
+        summary
+    }
+}
+
+// `cargo ... --no-run --message-format=json-render-diagnostics` 输出中测试（或 bench）可执行文件的路径，
+// 不包括为集成测试一并构建的 bin 本身
+pub fn executables(cargo_stdout: &str) -> Vec<PathBuf> {
+    let is_bench = |message: &serde_json::Value| {
+        message["target"]["kind"].as_array().is_some_and(|kinds| kinds.iter().any(|kind| kind == "bench"))
+    };
+    cargo_stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter(|message| message["profile"]["test"] == true || is_bench(message))
+        .filter_map(|message| message["executable"].as_str().map(PathBuf::from))
+        .collect()
+}
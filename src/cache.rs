@@ -0,0 +1,163 @@
+use super::{Exercise, ExerciseResult, ExerciseStatus};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    hash: String,
+    result: ExerciseResult,
+}
+
+// 以习题内容哈希为键的评测结果缓存，内容、配置与工具链都未变化时直接复用上一次的结果
+pub struct ResultCache {
+    path: PathBuf,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl ResultCache {
+    // 缓存文件不存在或已损坏时从空缓存开始
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read(path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        ResultCache { path: path.to_path_buf(), entries }
+    }
+
+    pub fn get(&self, name: &str, hash: &str) -> Option<ExerciseResult> {
+        self.entries
+            .get(name)
+            .filter(|entry| entry.hash == hash)
+            .map(|entry| entry.result.clone())
+    }
+
+    // 超时、评测环境错误与性能评测的结果（无论是否超出时间预算）取决于机器负载而非代码内容，不缓存
+    pub fn insert(&mut self, name: &str, hash: String, result: &ExerciseResult) {
+        let load_dependent = matches!(result.status, ExerciseStatus::Timeout | ExerciseStatus::InfraError);
+        if load_dependent || result.benchmark.is_some() {
+            self.entries.remove(name);
+            return;
+        }
+        self.entries.insert(name.to_string(), CacheEntry { hash, result: result.clone() });
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = fs::File::create(&self.path)?;
+        serde_json::to_writer(file, &self.entries)?;
+        Ok(())
+    }
+}
+
+// 评测环境的指纹，作为缓存键的一部分：评测器可执行文件本身的哈希（评测规则的任何改动都会使旧结果失效，
+// 无需手工维护版本号）以及 rustc 与 cargo 的版本信息
+pub fn environment_fingerprint() -> String {
+    let grader = std::env::current_exe()
+        .and_then(fs::read)
+        .map(|content| Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect::<String>())
+        .unwrap_or_default();
+    let toolchain = ["rustc", "cargo"].iter().map(|tool| {
+        Command::new(tool)
+            .arg("-vV")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+            .unwrap_or_default()
+    });
+    std::iter::once(grader).chain(toolchain).collect::<Vec<_>>().join("\n")
+}
+
+// 计算习题的内容哈希：评测环境的指纹、习题配置、习题目录下除 target 外的全部文件
+// （cargo 项目包括 Cargo.toml 与 Cargo.lock）以及隐藏测试（位于 `hidden_tests_dir` 下）与参考实现
+pub fn exercise_hash(exercise: &Exercise, hidden_tests_dir: Option<&Path>, environment: &str) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(environment);
+    hasher.update(serde_json::to_vec(exercise)?);
+
+    let path = exercise.full_path();
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect_files(&path, &mut files)?;
+    } else {
+        files.push(path.clone());
+    }
+    files.sort();
+
+    // 隐藏测试或参考实现变化时同样需要重新评测
+    let hidden = hidden_tests_dir.and_then(|dir| exercise.hidden_tests_path(dir));
+    if let Some(hidden) = hidden.filter(|hidden| hidden.is_file()) {
+        files.push(hidden);
+    }
+    if let Some(reference) = exercise.differential.as_ref().map(|spec| spec.reference_path()).filter(|r| r.is_file()) {
+        files.push(reference);
+    }
+
+    for file in files {
+        let relative = file.strip_prefix(&path).unwrap_or(&file);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        let content = fs::read(&file)?;
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(content);
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+pub fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "target" || name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn result(status: &str, benchmark: Option<serde_json::Value>) -> ExerciseResult {
+        serde_json::from_value(json!({
+            "name": "algorithm1",
+            "status": status,
+            "result": status == "pass",
+            "score": 10,
+            "benchmark": benchmark,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn caches_only_results_that_do_not_depend_on_machine_load() {
+        let mut cache = ResultCache::load(Path::new("/nonexistent/cache.json"));
+        cache.insert("algorithm1", "hash".to_string(), &result("pass", None));
+        assert!(cache.get("algorithm1", "hash").is_some());
+        assert!(cache.get("algorithm1", "other").is_none());
+
+        let timing = json!({
+            "budget_ms": 200, "tolerance": 0.1, "runs": 5,
+            "min_ms": 10.0, "median_ms": 12.0, "max_ms": 15.0, "within_budget": true,
+        });
+        cache.insert("algorithm1", "hash".to_string(), &result("pass", Some(timing)));
+        assert!(cache.get("algorithm1", "hash").is_none());
+
+        cache.insert("algorithm1", "hash".to_string(), &result("pass", None));
+        cache.insert("algorithm1", "hash".to_string(), &result("timeout", None));
+        assert!(cache.get("algorithm1", "hash").is_none());
+    }
+}
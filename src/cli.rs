@@ -0,0 +1,263 @@
+use crate::formats::ReportFormat;
+use crate::CleanPolicy;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: cargotest <command> [options]
+
+Commands:
+  all                 Grade every exercise and write the report
+  watch               Grade in order, stop at the first failing exercise and re-grade it on save
+  run <name>          Grade a single exercise and update its entry in the report
+  list                List exercises with their level, type, score and last result
+  hint <name>         Show an exercise's problem statement and hints
+  new <name>          Create an exercise from a template and register it in the config
+                      (requires --type and --level)
+  reset <name>        Restore an exercise's starting code from starting_code/ (tests are kept),
+                      backing up the current code to backups/ first
+  manifest            Record fingerprints of the official tests in test_manifest.json
+  report              Print the summary of the last report (and write --format outputs from it)
+  validate            Check the exercise config for problems and exit non-zero if it is invalid
+
+Options:
+  --level <level>     Only include exercises of the given difficulty tier from the config
+                      (e.g. easy, normal, hard)
+  --only-failed       Only include exercises that did not pass in the last report
+  --type <type>       Type of the exercise created by 'new': single_file, cargo_project or
+                      io_judge
+  --score <N>         Score of the exercise created by 'new' [default: the tier's default_score]
+  --from <rev>        Git revision 'reset' restores from [default: starting_code/ instead of git]
+                      or 'manifest' fingerprints [default: HEAD]
+  --restore-tests     Restore tests that differ from test_manifest.json before grading
+                      instead of marking the exercise as tests modified
+  --config <path>     Exercise config file [default: exercise_config.json]
+  --report <path>     Report file [default: report.json]
+  --no-cache          Grade every selected exercise even if its sources are unchanged
+  --hidden-tests <dir>
+                      Directory outside the repository holding the hidden test suites
+                      [default: $GRADER_HIDDEN_TESTS_DIR, hidden tests are skipped if unset]
+  --clean <policy>    Clean cargo exercises' build artifacts from the shared build directory:
+                      keep, on-success or always [default: keep]
+  --format <formats>  Extra report formats written next to the report, comma-separated or
+                      repeated: junit, markdown, html [default: json only]
+  -j, --jobs <N>      Number of exercises graded in parallel [default: 1]
+  -v, --verbose       Show more detail while grading, such as the rustfmt differences of
+                      exercises with a format check
+  -h, --help          Print this help";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliCommand {
+    All,
+    Watch,
+    Run { name: String },
+    List,
+    Hint { name: String },
+    New { name: String },
+    Reset { name: String },
+    Manifest,
+    Report,
+    Validate,
+    Help,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub level: Option<String>,
+    pub only_failed: bool,
+    pub exercise_type: Option<String>,
+    pub score: Option<i32>,
+    pub from_revision: Option<String>,
+    pub restore_tests: bool,
+    pub config_path: PathBuf,
+    pub report_path: PathBuf,
+    pub formats: Vec<ReportFormat>,
+    pub use_cache: bool,
+    pub hidden_tests_dir: Option<PathBuf>,
+    pub clean_policy: CleanPolicy,
+    pub jobs: usize,
+    pub verbose: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            level: None,
+            only_failed: false,
+            exercise_type: None,
+            score: None,
+            from_revision: None,
+            restore_tests: false,
+            config_path: PathBuf::from("exercise_config.json"),
+            report_path: PathBuf::from("report.json"),
+            formats: Vec::new(),
+            use_cache: true,
+            hidden_tests_dir: None,
+            clean_policy: CleanPolicy::default(),
+            jobs: 1,
+            verbose: false,
+        }
+    }
+}
+
+impl Options {
+    // 是否只评测了部分习题（此时需要与上一次的报告合并）
+    pub fn is_filtered(&self) -> bool {
+        self.level.is_some() || self.only_failed
+    }
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: CliCommand,
+    pub options: Options,
+}
+
+// 解析命令行参数（不含程序名），出错时返回可直接打印的错误信息
+pub fn parse_args(args: &[String]) -> Result<Cli, String> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        // `cargo run all -- --jobs 4` 会把分隔符 `--` 原样传进来
+        if arg == "--" {
+            continue;
+        }
+        if !arg.starts_with('-') {
+            positional.push(arg.clone());
+            continue;
+        }
+
+        // 同时支持 `--flag value` 与 `--flag=value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            match &inline_value {
+                Some(value) => Ok(value.clone()),
+                None => iter.next().cloned().ok_or_else(|| format!("Missing value for {}", name)),
+            }
+        };
+
+        match flag {
+            "--level" => options.level = Some(value(flag)?),
+            "--only-failed" => options.only_failed = true,
+            "--type" => options.exercise_type = Some(value(flag)?),
+            "--from" => options.from_revision = Some(value(flag)?),
+            "--restore-tests" => options.restore_tests = true,
+            "--score" => {
+                let score = value(flag)?;
+                options.score = match score.parse::<i32>() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => return Err(format!("Invalid value for --score: {}", score)),
+                };
+            }
+            "--no-cache" => options.use_cache = false,
+            "--hidden-tests" => options.hidden_tests_dir = Some(PathBuf::from(value(flag)?)),
+            "--clean" => options.clean_policy = CleanPolicy::parse(&value(flag)?)?,
+            "--config" => options.config_path = PathBuf::from(value(flag)?),
+            "--report" => options.report_path = PathBuf::from(value(flag)?),
+            "--format" => {
+                for format in value(flag)?.split(',').filter(|f| !f.is_empty()) {
+                    let format = ReportFormat::parse(format.trim())?;
+                    if !options.formats.contains(&format) {
+                        options.formats.push(format);
+                    }
+                }
+            }
+            "-j" | "--jobs" => {
+                let jobs = value(flag)?;
+                options.jobs = match jobs.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid value for --jobs: {}", jobs)),
+                };
+            }
+            "-v" | "--verbose" => options.verbose = true,
+            "-h" | "--help" => {
+                return Ok(Cli { command: CliCommand::Help, options });
+            }
+            _ => return Err(format!("Unknown option: {}", arg)),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next().as_deref() {
+        Some("all") => CliCommand::All,
+        Some("watch") => CliCommand::Watch,
+        Some("run") => match positional.next() {
+            Some(name) => CliCommand::Run { name },
+            None => return Err("Missing exercise name for 'run'".to_string()),
+        },
+        Some("list") => CliCommand::List,
+        Some("hint") => match positional.next() {
+            Some(name) => CliCommand::Hint { name },
+            None => return Err("Missing exercise name for 'hint'".to_string()),
+        },
+        Some("new") => match positional.next() {
+            Some(name) => CliCommand::New { name },
+            None => return Err("Missing exercise name for 'new'".to_string()),
+        },
+        Some("reset") => match positional.next() {
+            Some(name) => CliCommand::Reset { name },
+            None => return Err("Missing exercise name for 'reset'".to_string()),
+        },
+        Some("manifest") => CliCommand::Manifest,
+        Some("report") => CliCommand::Report,
+        Some("validate") => CliCommand::Validate,
+        Some("help") => CliCommand::Help,
+        Some(other) => return Err(format!("Unknown command: {}", other)),
+        None => return Err("Please provide a command".to_string()),
+    };
+
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument: {}", extra));
+    }
+
+    Ok(Cli { command, options })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_commands_with_their_arguments() {
+        assert_eq!(parse(&["all"]).unwrap().command, CliCommand::All);
+        assert_eq!(parse(&["run", "algorithm1.rs"]).unwrap().command, CliCommand::Run { name: "algorithm1.rs".to_string() });
+        assert_eq!(parse(&["--help"]).unwrap().command, CliCommand::Help);
+    }
+
+    #[test]
+    fn accepts_both_value_styles_and_the_cargo_separator() {
+        let cli = parse(&[
+            "all", "--", "--jobs", "4", "--level=hard", "--format", "junit,markdown", "--format=junit", "--hidden-tests", "/srv/hidden",
+        ])
+        .unwrap();
+        assert_eq!(cli.command, CliCommand::All);
+        assert_eq!(cli.options.jobs, 4);
+        assert_eq!(cli.options.level.as_deref(), Some("hard"));
+        assert_eq!(cli.options.formats, vec![ReportFormat::Junit, ReportFormat::Markdown]);
+        assert_eq!(cli.options.hidden_tests_dir, Some(PathBuf::from("/srv/hidden")));
+        assert!(cli.options.is_filtered());
+    }
+
+    #[test]
+    fn reports_missing_and_invalid_values() {
+        assert_eq!(parse(&["all", "--level"]).unwrap_err(), "Missing value for --level");
+        assert_eq!(parse(&["all", "-j", "0"]).unwrap_err(), "Invalid value for --jobs: 0");
+        assert_eq!(parse(&["run"]).unwrap_err(), "Missing exercise name for 'run'");
+        assert_eq!(parse(&[]).unwrap_err(), "Please provide a command");
+    }
+
+    #[test]
+    fn rejects_unknown_flags_commands_and_extra_arguments() {
+        assert_eq!(parse(&["all", "--fast"]).unwrap_err(), "Unknown option: --fast");
+        assert_eq!(parse(&["grade"]).unwrap_err(), "Unknown command: grade");
+        assert_eq!(parse(&["list", "extra"]).unwrap_err(), "Unexpected argument: extra");
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// 单文件习题默认使用的 edition，与根 crate 及各 cargo 项目习题一致
+pub const DEFAULT_EDITION: &str = "2021";
+
+pub const EDITIONS: [&str; 4] = ["2015", "2018", "2021", "2024"];
+
+// 配置中的 `opt_level`：0 到 3 的数字，或按体积优化的 "s" / "z"
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OptLevel {
+    Level(u8),
+    Named(String),
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptLevel::Level(level) => write!(f, "{}", level),
+            OptLevel::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+// 单文件习题编译警告的处理方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningPolicy {
+    // 以 `-A warnings` 编译，不报告警告
+    Allow,
+    // 在控制台与报告中列出警告，不影响结论（默认）
+    #[default]
+    Warn,
+    // 以 `-D warnings` 编译，任何警告都使编译失败
+    Deny,
+}
+
+impl WarningPolicy {
+    pub fn is_warn(&self) -> bool {
+        *self == WarningPolicy::Warn
+    }
+
+    pub fn rustc_flag(self) -> Option<&'static str> {
+        match self {
+            WarningPolicy::Allow => Some("-Awarnings"),
+            WarningPolicy::Warn => None,
+            WarningPolicy::Deny => Some("-Dwarnings"),
+        }
+    }
+}
+
+// rustc 输出中的各条警告，格式为 `<位置>: <警告>`；不包括 `N warnings emitted` 这样的汇总
+pub fn warnings(stderr: &str) -> Vec<String> {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let mut warnings = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(message) = line.strip_prefix("warning: ") else {
+            continue;
+        };
+        if message.ends_with(" emitted") {
+            continue;
+        }
+        let location = lines
+            .get(index + 1)
+            .and_then(|next| next.trim_start().strip_prefix("--> "))
+            .map(str::trim);
+        warnings.push(match location {
+            Some(location) => format!("{}: {}", location, message),
+            None => message.to_string(),
+        });
+    }
+    warnings
+}
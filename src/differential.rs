@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// 差分测试的参考实现所在目录，与隐藏测试一样放在学生的习题目录之外
+pub const REFERENCES_DIR: &str = "references";
+
+// 差分测试阶段在日志中的名称
+pub const DIFFERENTIAL_PHASE: &str = "differential";
+
+// 与参考实现拼接的测试框架，编译进评测器
+const HARNESS: &str = include_str!("../templates/differential_harness.rs");
+
+const DEFAULT_CASES: usize = 500;
+const DEFAULT_SEED: u64 = 1;
+
+// 配置中的 `differential`：参考实现文件（相对 references/）、随机用例数与固定种子
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DifferentialSpec {
+    pub reference: String,
+    #[serde(default = "default_cases")]
+    pub cases: usize,
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+}
+
+fn default_cases() -> usize {
+    DEFAULT_CASES
+}
+
+fn default_seed() -> u64 {
+    DEFAULT_SEED
+}
+
+impl DifferentialSpec {
+    pub fn reference_path(&self) -> PathBuf {
+        Path::new(REFERENCES_DIR).join(&self.reference)
+    }
+
+    // 差分测试模块的源码：测试框架、参考实现以及按配置的种子与用例数运行的 #[test]
+    pub fn test_module(&self, reference: &str) -> String {
+        format!(
+            "{}\n{}\n#[test]\nfn differential() {{\n    run::<Case>({}, {});\n}}\n",
+            HARNESS, reference, self.seed, self.cases
+        )
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+// 格式检查阶段在日志中的名称
+pub const FORMAT_PHASE: &str = "format";
+
+// rustfmt --check 输出中每段差异的开头：`Diff in <文件>:<行>:`
+const DIFF_PREFIX: &str = "Diff in ";
+
+// verbose 模式下每个文件最多打印的差异行数
+const EXCERPT_MAX_LINES: usize = 20;
+
+// 配置中的 `format_check`（可写在难度或习题上，习题上的优先）：`"off"` 不检查（默认），`"warn"` 只报告，
+// `{"deduct": N}` 每个格式不符合 rustfmt 的文件扣 N 分
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatPolicy {
+    Off,
+    Warn,
+    Deduct(i32),
+}
+
+// 一个格式不符合 rustfmt 的文件及其差异
+pub struct FileDiff {
+    pub file: String,
+    pub diff: String,
+}
+
+impl FileDiff {
+    pub fn excerpt(&self) -> Vec<&str> {
+        let lines: Vec<&str> = self.diff.lines().collect();
+        let mut excerpt: Vec<&str> = lines.iter().take(EXCERPT_MAX_LINES).copied().collect();
+        if lines.len() > EXCERPT_MAX_LINES {
+            excerpt.push("...");
+        }
+        excerpt
+    }
+}
+
+// 按文件汇总 rustfmt --check 输出中的差异，文件按首次出现的顺序排列
+pub fn file_diffs(output: &str) -> Vec<FileDiff> {
+    let mut diffs: Vec<FileDiff> = Vec::new();
+    let mut current: Option<usize> = None;
+    for line in output.lines() {
+        if let Some(file) = line.strip_prefix(DIFF_PREFIX).and_then(diff_file) {
+            let index = match diffs.iter().position(|diff| diff.file == file) {
+                Some(index) => index,
+                None => {
+                    diffs.push(FileDiff { file: file.to_string(), diff: String::new() });
+                    diffs.len() - 1
+                }
+            };
+            current = Some(index);
+        }
+        if let Some(index) = current {
+            diffs[index].diff.push_str(line);
+            diffs[index].diff.push('\n');
+        }
+    }
+    diffs
+}
+
+// `<文件>:<行>:` 中的文件部分
+fn diff_file(location: &str) -> Option<&str> {
+    let (file, line) = location.strip_suffix(':')?.rsplit_once(':')?;
+    line.parse::<usize>().is_ok().then_some(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_diff_blocks_by_file_in_order_of_appearance() {
+        let output = "\
+Diff in /p/src/lib.rs:1:
+-fn a( ) {}
++fn a() {}
+Diff in /p/src/main.rs:3:
+-fn main( ) {}
++fn main() {}
+Diff in /p/src/lib.rs:12:
+-fn b( ) {}
++fn b() {}
+";
+        let diffs = file_diffs(output);
+        let files: Vec<&str> = diffs.iter().map(|diff| diff.file.as_str()).collect();
+        assert_eq!(files, ["/p/src/lib.rs", "/p/src/main.rs"]);
+        assert_eq!(
+            diffs[0].diff,
+            "Diff in /p/src/lib.rs:1:\n-fn a( ) {}\n+fn a() {}\nDiff in /p/src/lib.rs:12:\n-fn b( ) {}\n+fn b() {}\n"
+        );
+        assert_eq!(diffs[1].diff, "Diff in /p/src/main.rs:3:\n-fn main( ) {}\n+fn main() {}\n");
+    }
+
+    #[test]
+    fn ignores_lines_before_the_first_diff_and_headers_without_a_line_number() {
+        let output = "warning: unstable option\nDiff in C:\\p\\a.rs:\n-x\nDiff in C:\\p\\b.rs:7:\n-y\n";
+        let diffs = file_diffs(output);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].file, "C:\\p\\b.rs");
+        assert_eq!(diffs[0].diff, "Diff in C:\\p\\b.rs:7:\n-y\n");
+    }
+
+    #[test]
+    fn parses_the_file_before_the_line_number() {
+        assert_eq!(diff_file("src/a:b.rs:10:"), Some("src/a:b.rs"));
+        assert_eq!(diff_file("src/a.rs:x:"), None);
+        assert_eq!(diff_file("src/a.rs:10"), None);
+    }
+
+    #[test]
+    fn truncates_long_excerpts() {
+        let diff = FileDiff { file: "a.rs".to_string(), diff: "-x\n".repeat(EXCERPT_MAX_LINES + 5) };
+        let excerpt = diff.excerpt();
+        assert_eq!(excerpt.len(), EXCERPT_MAX_LINES + 1);
+        assert_eq!(excerpt.last(), Some(&"..."));
+    }
+}
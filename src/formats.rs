@@ -0,0 +1,262 @@
+use super::{ExerciseResult, ExerciseStatus, Report};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// report.json 之外可额外生成的报告格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "json" => Ok(ReportFormat::Json),
+            "junit" | "xml" => Ok(ReportFormat::Junit),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!("Unknown report format: {} (expected json, junit, markdown or html)", value)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Junit => "xml",
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+// 按难度分组的习题结果，顺序与配置一致
+pub type Groups<'a> = [(String, Vec<&'a ExerciseResult>)];
+
+// 在 report.json 旁边写出其他格式的报告（同名、不同扩展名），返回写出的文件路径
+pub fn write_reports(
+    report: &Report,
+    groups: &Groups,
+    report_path: &Path,
+    formats: &[ReportFormat],
+) -> io::Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for format in formats {
+        let content = match format {
+            // report.json 本身由调用方写出
+            ReportFormat::Json => continue,
+            ReportFormat::Junit => render_junit(report, groups),
+            ReportFormat::Markdown => render_markdown(report, groups),
+            ReportFormat::Html => render_html(report, groups),
+        };
+        let path = report_path.with_extension(format.extension());
+        fs::write(&path, content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn seconds(duration_ms: u64) -> String {
+    format!("{:.3}", duration_ms as f64 / 1000.0)
+}
+
+fn status_name(status: ExerciseStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 不允许大多数控制字符（例如测试输出中的 ANSI 颜色转义）
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// JUnit XML：每个难度一个 testsuite，每道习题一个 testcase；超时与评测环境错误记为 error，
+// 带未完成标记的习题记为 skipped，其余未通过记为 failure
+fn render_junit(report: &Report, groups: &Groups) -> String {
+    let is_error = |r: &ExerciseResult| matches!(r.status, ExerciseStatus::Timeout | ExerciseStatus::InfraError);
+    let is_pending = |r: &ExerciseResult| r.status == ExerciseStatus::Pending;
+    let count_errors = |results: &[&ExerciseResult]| results.iter().filter(|r| is_error(r)).count();
+    let count_failures = |results: &[&ExerciseResult]| {
+        results.iter().filter(|r| !r.result && !is_error(r) && !is_pending(r)).count()
+    };
+    let count_skipped = |results: &[&ExerciseResult]| results.iter().filter(|r| is_pending(r)).count();
+    let all: Vec<&ExerciseResult> = report.exercises.iter().collect();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"cargotest\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        all.len(),
+        count_failures(&all),
+        count_errors(&all),
+        count_skipped(&all),
+        report.statistics.total_time
+    ));
+
+    for (level, results) in groups {
+        let time: u64 = results.iter().map(|r| r.duration_ms).sum();
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            escape_xml(level),
+            results.len(),
+            count_failures(results),
+            count_errors(results),
+            count_skipped(results),
+            seconds(time)
+        ));
+
+        for result in results {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                escape_xml(level),
+                escape_xml(&result.name),
+                seconds(result.duration_ms)
+            ));
+            if result.result {
+                xml.push_str("/>\n");
+                continue;
+            }
+            if is_pending(result) {
+                xml.push_str(&format!(
+                    ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                    escape_xml(result.message.as_deref().unwrap_or(result.status.label()))
+                ));
+                continue;
+            }
+            let tag = if is_error(result) { "error" } else { "failure" };
+            xml.push_str(&format!(
+                ">\n      <{} type=\"{}\" message=\"{}\">{}</{}>\n    </testcase>\n",
+                tag,
+                status_name(result.status),
+                escape_xml(result.status.label()),
+                escape_xml(result.message.as_deref().unwrap_or("")),
+                tag
+            ));
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// (通过数, 得分, 计入部分分后的得分)
+fn group_summary(results: &[&ExerciseResult]) -> (usize, i32, f64) {
+    let passed = results.iter().filter(|r| r.result).count();
+    let score = results.iter().map(|r| r.score).sum();
+    let points = results.iter().map(|r| r.points).sum();
+    (passed, score, points)
+}
+
+// Markdown 汇总：总览 + 每个难度一张表
+fn render_markdown(report: &Report, groups: &Groups) -> String {
+    let statistics = &report.statistics;
+    let mut md = String::from("# Grading report\n\n");
+    md.push_str(&format!(
+        "**{}/{}** exercises passed, total score **{}**, total points **{:.2}**, time {}s.\n",
+        statistics.total_successes,
+        statistics.total_exercises,
+        statistics.total_score,
+        statistics.total_points,
+        statistics.total_time
+    ));
+
+    for (level, results) in groups {
+        let (passed, score, points) = group_summary(results);
+        md.push_str(&format!(
+            "\n## {} ({}/{} passed, score {}, points {:.2})\n\n",
+            level,
+            passed,
+            results.len(),
+            score,
+            points
+        ));
+        md.push_str("| Exercise | Status | Score | Points | Time (s) |\n");
+        md.push_str("|---|---|---:|---:|---:|\n");
+        for result in results {
+            md.push_str(&format!(
+                "| {} | {} | {} | {:.2} | {} |\n",
+                result.name.replace('|', "\\|"),
+                status_name(result.status),
+                result.score,
+                result.points,
+                seconds(result.duration_ms)
+            ));
+        }
+    }
+
+    md
+}
+
+// HTML 汇总：与 Markdown 相同的结构，状态按颜色区分
+fn render_html(report: &Report, groups: &Groups) -> String {
+    let statistics = &report.statistics;
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Grading report</title>\n\
+         <style>\n\
+         body { font-family: sans-serif; }\n\
+         table { border-collapse: collapse; margin-bottom: 1em; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; }\n\
+         .pass { color: #1a7f37; }\n\
+         .fail { color: #cf222e; }\n\
+         .pending { color: #9a6700; }\n\
+         </style>\n</head>\n<body>\n<h1>Grading report</h1>\n",
+    );
+    html.push_str(&format!(
+        "<p><b>{}/{}</b> exercises passed, total score <b>{}</b>, total points <b>{:.2}</b>, time {}s.</p>\n",
+        statistics.total_successes,
+        statistics.total_exercises,
+        statistics.total_score,
+        statistics.total_points,
+        statistics.total_time
+    ));
+
+    for (level, results) in groups {
+        let (passed, score, points) = group_summary(results);
+        html.push_str(&format!(
+            "<h2>{} ({}/{} passed, score {}, points {:.2})</h2>\n<table>\n\
+             <tr><th>Exercise</th><th>Status</th><th>Score</th><th>Points</th><th>Time (s)</th></tr>\n",
+            escape_xml(level),
+            passed,
+            results.len(),
+            score,
+            points
+        ));
+        for result in results {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{:.2}</td><td>{}</td></tr>\n",
+                escape_xml(&result.name),
+                match result.status {
+                    ExerciseStatus::Pass => "pass",
+                    ExerciseStatus::Pending => "pending",
+                    _ => "fail",
+                },
+                status_name(result.status),
+                result.score,
+                result.points,
+                seconds(result.duration_ms)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,108 @@
+use super::marker::is_marker_line;
+use super::Exercise;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// 源码开头注释中以此开头的段落视为提示
+const HINT_PREFIX: &str = "Hint:";
+
+// 从习题源码开头的注释中提取的题目描述
+pub struct Statement {
+    // 去掉提示段落后的描述，段落之间保留空行
+    pub lines: Vec<String>,
+    pub hint: Option<String>,
+}
+
+// 题目描述所在的源文件：单文件习题为其本身，cargo 项目为 src/main.rs 或 src/lib.rs
+fn statement_source(exercise: &Exercise) -> Option<PathBuf> {
+    let path = exercise.full_path();
+    if !path.is_dir() {
+        return Some(path);
+    }
+    ["src/main.rs", "src/lib.rs"].iter().map(|file| path.join(file)).find(|file| file.is_file())
+}
+
+// 读取习题的题目描述；源文件开头没有注释时返回 None
+pub fn read_statement(exercise: &Exercise) -> io::Result<Option<Statement>> {
+    let Some(source) = statement_source(exercise) else {
+        return Ok(None);
+    };
+    Ok(parse_statement(&fs::read_to_string(source)?))
+}
+
+// 支持 `/* ... */` 块注释与连续的 `//` 行注释两种写法，忽略未完成标记
+fn parse_statement(source: &str) -> Option<Statement> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || is_marker_line(line))
+        .peekable();
+    let mut comment = Vec::new();
+
+    if lines.peek()?.starts_with("/*") {
+        for line in lines {
+            let (text, done) = match line.find("*/") {
+                Some(end) => (&line[..end], true),
+                None => (line, false),
+            };
+            comment.push(text.trim_start_matches("/*").trim_start_matches('*').trim().to_string());
+            if done {
+                break;
+            }
+        }
+    } else {
+        for line in lines.take_while(|line| line.starts_with("//")).filter(|line| !is_marker_line(line)) {
+            comment.push(line.trim_start_matches('/').trim().to_string());
+        }
+    }
+
+    let mut statement = Statement { lines: Vec::new(), hint: None };
+    for paragraph in comment.split(|line| line.is_empty()).filter(|p| !p.is_empty()) {
+        match paragraph[0].strip_prefix(HINT_PREFIX) {
+            Some(first) if statement.hint.is_none() => {
+                let rest = paragraph[1..].iter().map(String::as_str);
+                let hint: Vec<&str> = std::iter::once(first.trim()).chain(rest).collect();
+                statement.hint = Some(hint.join(" "));
+            }
+            _ => {
+                if !statement.lines.is_empty() {
+                    statement.lines.push(String::new());
+                }
+                statement.lines.extend(paragraph.iter().cloned());
+            }
+        }
+    }
+
+    if statement.lines.is_empty() && statement.hint.is_none() {
+        None
+    } else {
+        Some(statement)
+    }
+}
+
+// 与习题放在同一目录下的 PDF 题目文档
+pub fn statement_documents(exercise: &Exercise) -> Vec<PathBuf> {
+    let path = exercise.full_path();
+    let Some(Ok(entries)) = path.parent().map(fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut documents: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|file| file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")))
+        .collect();
+    documents.sort();
+    documents
+}
+
+// 按顺序给出的全部提示：配置中的 `hint`、`hints`；配置中没有提示时使用源码注释中的提示
+pub fn exercise_hints(exercise: &Exercise) -> Vec<String> {
+    let mut hints: Vec<String> = exercise.hint.iter().chain(&exercise.hints).cloned().collect();
+    if hints.is_empty() {
+        if let Ok(Some(Statement { hint: Some(hint), .. })) = read_statement(exercise) {
+            hints.push(hint);
+        }
+    }
+    hints
+}
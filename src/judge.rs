@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// io_judge 习题中存放 `.in` / `.out` 用例的目录（相对习题目录）
+pub const CASES_DIR: &str = "cases";
+
+// 浮点比较默认允许的误差（绝对误差或相对误差满足其一即可）
+pub const DEFAULT_FLOAT_TOLERANCE: f64 = 1e-6;
+
+// 程序输出与期望输出的比较方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareMode {
+    // 逐字节一致
+    Exact,
+    // 忽略空白的差异，按空白分隔的记号逐个比较（默认）
+    #[default]
+    Whitespace,
+    // 同 whitespace，但两边都是数字的记号按误差比较
+    Float,
+}
+
+impl CompareMode {
+    pub fn is_whitespace(&self) -> bool {
+        *self == CompareMode::Whitespace
+    }
+}
+
+// 单个用例的评测结论
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseVerdict {
+    Accepted,
+    WrongAnswer,
+    RuntimeError,
+    Timeout,
+    MemoryLimitExceeded,
+}
+
+impl CaseVerdict {
+    pub fn label(self) -> &'static str {
+        match self {
+            CaseVerdict::Accepted => "AC",
+            CaseVerdict::WrongAnswer => "WA",
+            CaseVerdict::RuntimeError => "RE",
+            CaseVerdict::Timeout => "TLE",
+            CaseVerdict::MemoryLimitExceeded => "MLE",
+        }
+    }
+}
+
+// 报告中每个用例的结果
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CaseResult {
+    pub name: String,
+    pub verdict: CaseVerdict,
+    pub duration_ms: u64,
+}
+
+pub struct TestCase {
+    pub name: String,
+    pub input: PathBuf,
+    pub expected: PathBuf,
+}
+
+// 习题 cases/ 目录下成对的 `<name>.in` 与 `<name>.out`，按名称排序（纯数字名称按数值）；缺少 .out 时报错
+pub fn test_cases(project: &Path) -> io::Result<Vec<TestCase>> {
+    let dir = project.join(CASES_DIR);
+    let mut cases = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let input = entry?.path();
+        if input.extension().is_none_or(|ext| ext != "in") {
+            continue;
+        }
+        let expected = input.with_extension("out");
+        if !expected.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} has no matching {}", input.display(), expected.display()),
+            ));
+        }
+        let name = input.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        cases.push(TestCase { name, input, expected });
+    }
+    cases.sort_by(|a, b| match (a.name.parse::<u64>(), b.name.parse::<u64>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y),
+        _ => a.name.cmp(&b.name),
+    });
+    Ok(cases)
+}
+
+// 比较程序输出与期望输出，不一致时返回第一处差异的说明
+pub fn compare(mode: CompareMode, tolerance: f64, expected: &str, actual: &str) -> Result<(), String> {
+    match mode {
+        CompareMode::Exact => first_difference("line", expected.split('\n'), actual.split('\n'), |e, a| e == a),
+        CompareMode::Whitespace => {
+            first_difference("token", expected.split_whitespace(), actual.split_whitespace(), |e, a| e == a)
+        }
+        CompareMode::Float => first_difference("token", expected.split_whitespace(), actual.split_whitespace(), |e, a| {
+            e == a || floats_match(e, a, tolerance)
+        }),
+    }
+}
+
+// 逐项比较两个序列，返回第一处不一致（含一方提前结束）的说明
+fn first_difference<'a>(
+    unit: &str,
+    mut expected: impl Iterator<Item = &'a str>,
+    mut actual: impl Iterator<Item = &'a str>,
+    equal: impl Fn(&str, &str) -> bool,
+) -> Result<(), String> {
+    let mut index = 1;
+    loop {
+        match (expected.next(), actual.next()) {
+            (None, None) => return Ok(()),
+            (Some(e), Some(a)) if equal(e, a) => index += 1,
+            (e, a) => return Err(format!("{} {}: expected {}, got {}", unit, index, describe(e), describe(a))),
+        }
+    }
+}
+
+fn floats_match(expected: &str, actual: &str, tolerance: f64) -> bool {
+    match (expected.parse::<f64>(), actual.parse::<f64>()) {
+        (Ok(e), Ok(a)) => (e - a).abs() <= tolerance || (e - a).abs() <= tolerance * e.abs(),
+        _ => false,
+    }
+}
+
+fn describe(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("`{}`", value),
+        None => "end of output".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_mode_compares_lines_byte_for_byte() {
+        assert_eq!(compare(CompareMode::Exact, 0.0, "1 2\n3\n", "1 2\n3\n"), Ok(()));
+        assert_eq!(
+            compare(CompareMode::Exact, 0.0, "1 2\n3\n", "1 2 \n3\n"),
+            Err("line 1: expected `1 2`, got `1 2 `".to_string())
+        );
+        assert_eq!(compare(CompareMode::Exact, 0.0, "3\n", "3"), Err("line 2: expected ``, got end of output".to_string()));
+    }
+
+    #[test]
+    fn whitespace_mode_ignores_trailing_and_repeated_whitespace() {
+        assert_eq!(compare(CompareMode::Whitespace, 0.0, "1 2\n3\n", "1  2 \r\n3"), Ok(()));
+        assert_eq!(
+            compare(CompareMode::Whitespace, 0.0, "1 2 3", "1 2"),
+            Err("token 3: expected `3`, got end of output".to_string())
+        );
+        assert_eq!(
+            compare(CompareMode::Whitespace, 0.0, "1.0", "1"),
+            Err("token 1: expected `1.0`, got `1`".to_string())
+        );
+    }
+
+    #[test]
+    fn float_mode_accepts_absolute_or_relative_error_within_tolerance() {
+        assert_eq!(compare(CompareMode::Float, 1e-6, "0.333333 yes", "0.3333334 yes"), Ok(()));
+        assert_eq!(compare(CompareMode::Float, 1e-6, "1000000", "1000000.9"), Ok(()));
+        assert_eq!(compare(CompareMode::Float, 1e-6, "1.0", "1"), Ok(()));
+        assert_eq!(
+            compare(CompareMode::Float, 1e-6, "0.5", "0.500002"),
+            Err("token 1: expected `0.5`, got `0.500002`".to_string())
+        );
+        assert!(compare(CompareMode::Float, 1e-6, "yes", "YES").is_err());
+    }
+
+    #[test]
+    fn pairs_cases_and_sorts_numeric_names_by_value() {
+        let project = std::env::temp_dir().join(format!("cargotest-judge-{}", std::process::id()));
+        let dir = project.join(CASES_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["10", "2", "1", "edge"] {
+            fs::write(dir.join(format!("{}.in", name)), "").unwrap();
+            fs::write(dir.join(format!("{}.out", name)), "").unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        let names: Vec<String> = test_cases(&project).unwrap().into_iter().map(|case| case.name).collect();
+        assert_eq!(names, ["1", "2", "10", "edge"]);
+
+        fs::write(dir.join("3.in"), "").unwrap();
+        let error = test_cases(&project).err().expect("a case without .out is reported");
+        assert!(error.to_string().contains("has no matching"), "{}", error);
+        fs::remove_dir_all(&project).unwrap();
+    }
+}
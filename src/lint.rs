@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// clippy 阶段在日志中的名称
+pub const CLIPPY_PHASE: &str = "clippy";
+
+// 配置中的 `clippy`：`"ignore"` 不运行 clippy，`"warn"` 只报告（默认），`{"deduct": N}` 每条 lint 扣 N 分，
+// `"deny"` 有任何 lint 即不通过
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClippyPolicy {
+    Ignore,
+    #[default]
+    Warn,
+    Deduct(i32),
+    Deny,
+}
+
+impl ClippyPolicy {
+    pub fn is_warn(&self) -> bool {
+        *self == ClippyPolicy::Warn
+    }
+}
+
+// 报告中的一条 clippy lint
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Lint {
+    // 如 `clippy::needless_range_loop`
+    pub name: String,
+    pub level: String,
+    pub message: String,
+    // `<文件>:<行>:<列>`
+    pub location: String,
+}
+
+impl Lint {
+    pub fn describe(&self) -> String {
+        format!("{}: {} [{}]", self.location, self.message, self.name)
+    }
+}
+
+// JSON 输出中的一行对应的诊断：兼容 `cargo clippy --message-format=json`（包在 compiler-message 中）
+// 与 `clippy-driver --error-format=json`（每行一条诊断），其他消息（如构建产物）为 None
+fn diagnostic(value: serde_json::Value) -> Option<serde_json::Value> {
+    match value["reason"].as_str() {
+        Some("compiler-message") => Some(value["message"].clone()),
+        Some(_) => None,
+        None => value.get("$message_type").is_some().then_some(value),
+    }
+}
+
+fn diagnostics(output: &str) -> impl Iterator<Item = serde_json::Value> + '_ {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(diagnostic)
+}
+
+// 输出中的 clippy lint，不包括 rustc 自身的警告（如 dead_code）；相对路径相对 `base` 解析
+pub fn clippy_lints(output: &str, base: &Path) -> Vec<Lint> {
+    diagnostics(output)
+        .filter_map(|diagnostic| {
+            let name = diagnostic["code"]["code"].as_str().filter(|code| code.starts_with("clippy::"))?;
+            let span = diagnostic["spans"]
+                .as_array()
+                .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true))?;
+            let location = format!(
+                "{}:{}:{}",
+                base.join(span["file_name"].as_str().unwrap_or_default()).display(),
+                span["line_start"],
+                span["column_start"]
+            );
+            Some(Lint {
+                name: name.to_string(),
+                level: diagnostic["level"].as_str().unwrap_or_default().to_string(),
+                message: diagnostic["message"].as_str().unwrap_or_default().to_string(),
+                location,
+            })
+        })
+        .collect()
+}
+
+// 输出中是否有 error 级别的诊断：默认 deny 的 clippy lint（如 correctness 类）或代码本身的错误
+pub fn has_errors(output: &str) -> bool {
+    diagnostics(output).any(|diagnostic| diagnostic["level"] == "error")
+}
+
+// 把输出中的诊断换成编译器格式渲染后的文本、去掉其他 JSON 消息，便于阅读日志；非 JSON 的行原样保留
+pub fn rendered(output: &str) -> String {
+    let mut text = String::new();
+    for line in output.lines() {
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(value) => {
+                if let Some(rendered) = diagnostic(value).as_ref().and_then(|d| d["rendered"].as_str()) {
+                    text.push_str(rendered);
+                }
+            }
+            Err(_) => {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // clippy-driver --error-format=json 的输出：每行一条诊断
+    const DRIVER_OUTPUT: &str = concat!(
+        r#"{"$message_type":"diagnostic","message":"writing `&Vec` instead of `&[_]`","code":{"code":"clippy::ptr_arg","explanation":null},"level":"warning","spans":[{"file_name":"a.rs","line_start":1,"column_start":13,"is_primary":true}],"rendered":"warning: writing `&Vec`\n"}"#,
+        "\n",
+        r#"{"$message_type":"diagnostic","message":"equal expressions as operands to `==`","code":{"code":"clippy::eq_op","explanation":null},"level":"error","spans":[{"file_name":"a.rs","line_start":1,"column_start":2,"is_primary":false},{"file_name":"a.rs","line_start":1,"column_start":47,"is_primary":true}],"rendered":"error: equal expressions\n"}"#,
+        "\n",
+        r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"rendered":"error: aborting due to 1 previous error\n"}"#,
+        "\n",
+    );
+
+    #[test]
+    fn collects_lints_from_clippy_driver_output() {
+        let lints = clippy_lints(DRIVER_OUTPUT, Path::new(""));
+        let described: Vec<String> = lints.iter().map(Lint::describe).collect();
+        assert_eq!(
+            described,
+            [
+                "a.rs:1:13: writing `&Vec` instead of `&[_]` [clippy::ptr_arg]",
+                "a.rs:1:47: equal expressions as operands to `==` [clippy::eq_op]",
+            ]
+        );
+        assert_eq!(lints[1].level, "error");
+        assert!(has_errors(DRIVER_OUTPUT));
+    }
+
+    #[test]
+    fn collects_clippy_lints_from_cargo_messages_relative_to_the_project() {
+        let output = concat!(
+            r#"{"reason":"compiler-artifact","target":{"name":"p"}}"#,
+            "\n",
+            r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[{"file_name":"src/main.rs","line_start":2,"column_start":9,"is_primary":true}]}}"#,
+            "\n",
+            r#"{"reason":"compiler-message","message":{"message":"the loop variable `i` is only used to index `v`","code":{"code":"clippy::needless_range_loop","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","line_start":4,"column_start":14,"is_primary":true}]}}"#,
+            "\n",
+            "    Finished `dev` profile\n",
+        );
+        let lints = clippy_lints(output, Path::new("exercises/normal/p"));
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].name, "clippy::needless_range_loop");
+        assert_eq!(lints[0].location, "exercises/normal/p/src/lib.rs:4:14");
+        assert!(!has_errors(output));
+    }
+
+    #[test]
+    fn renders_diagnostics_and_keeps_plain_lines() {
+        let output = format!("{}error: could not compile `p`\n", DRIVER_OUTPUT);
+        assert_eq!(
+            rendered(&output),
+            "warning: writing `&Vec`\nerror: equal expressions\nerror: aborting due to 1 previous error\nerror: could not compile `p`\n"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+// 失败摘要最多打印的行数
+const EXCERPT_MAX_LINES: usize = 20;
+
+// 某个评测阶段（编译、测试、clippy）的完整输出
+pub struct PhaseLog {
+    pub phase: String,
+    pub command: String,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    // 评测器对该阶段的结论说明（如 io_judge 用例与期望输出的差异），有则优先作为摘要
+    pub note: Option<String>,
+    // 运行习题测试的阶段中测试进程的峰值常驻内存（KB）
+    pub peak_rss_kb: Option<u64>,
+}
+
+impl PhaseLog {
+    pub fn new(phase: &str, command: String, success: bool, output: &Output) -> Self {
+        PhaseLog {
+            phase: phase.to_string(),
+            command,
+            success,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            note: None,
+            peak_rss_kb: None,
+        }
+    }
+
+    // 把同一阶段后续命令（如 cargo 项目的各个测试程序）的输出并入本日志，阶段在全部命令都成功时才算成功
+    pub fn append(&mut self, other: PhaseLog) {
+        self.command = format!("{}\n{}", self.command, other.command);
+        self.success &= other.success;
+        self.stdout.push_str(&other.stdout);
+        self.stderr.push_str(&other.stderr);
+        self.note = self.note.take().or(other.note);
+        self.peak_rss_kb = self.peak_rss_kb.max(other.peak_rss_kb);
+    }
+
+    // 只保留阶段的结论与测试通过数，去掉测试名、断言信息与回溯，用于学生不可见的测试套件
+    pub fn redact(&mut self, counts: Option<(usize, usize)>) {
+        self.stdout = match counts {
+            Some((passed, failed)) => format!("{} passed; {} failed (details are not shown)\n", passed, failed),
+            None => "(details are not shown)\n".to_string(),
+        };
+        self.stderr.clear();
+        self.note = None;
+    }
+
+    // 从输出中截取最有用的片段：优先取失败测试的 panic 信息，其次取第一条编译错误
+    pub fn excerpt(&self) -> Vec<String> {
+        if let Some(note) = &self.note {
+            return note.lines().take(EXCERPT_MAX_LINES).map(str::to_string).collect();
+        }
+        let text = format!("{}\n{}", self.stdout, self.stderr);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let excerpt = failing_test_block(&lines)
+            .or_else(|| first_error_block(&lines))
+            .unwrap_or_else(|| tail(&lines));
+
+        excerpt.into_iter().take(EXCERPT_MAX_LINES).map(str::to_string).collect()
+    }
+}
+
+// libtest 的 `---- name stdout ----` 段落，到回溯信息或下一段为止
+fn failing_test_block<'a>(lines: &[&'a str]) -> Option<Vec<&'a str>> {
+    let start = lines.iter().position(|l| l.starts_with("---- ") && l.ends_with(" ----"))?;
+    let block = lines[start..]
+        .iter()
+        .enumerate()
+        .take_while(|(i, l)| {
+            *i == 0
+                || !(l.starts_with("---- ")
+                    || l.starts_with("stack backtrace:")
+                    || l.starts_with("note: run with `RUST_BACKTRACE")
+                    || l.starts_with("failures:"))
+        })
+        .map(|(_, l)| *l)
+        .collect();
+    Some(trim_blank(block))
+}
+
+// 第一条 `error...` 诊断，到空行为止；跳过 cargo 的汇总性错误
+fn first_error_block<'a>(lines: &[&'a str]) -> Option<Vec<&'a str>> {
+    let is_summary = |l: &str| {
+        l.starts_with("error: could not compile") || l.starts_with("error: test failed")
+    };
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("error") && !is_summary(l))
+        .or_else(|| lines.iter().position(|l| l.starts_with("error")))?;
+    let block = lines[start..]
+        .iter()
+        .enumerate()
+        .take_while(|(i, l)| *i == 0 || !l.trim().is_empty())
+        .map(|(_, l)| *l)
+        .collect();
+    Some(block)
+}
+
+fn tail<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let lines = trim_blank(lines.to_vec());
+    lines[lines.len().saturating_sub(EXCERPT_MAX_LINES / 2)..].to_vec()
+}
+
+fn trim_blank(mut lines: Vec<&str>) -> Vec<&str> {
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    while lines.first().is_some_and(|l| l.trim().is_empty()) {
+        lines.remove(0);
+    }
+    lines
+}
+
+// 将一道习题各阶段的完整输出写入 `<log_dir>/<name>.log`，返回日志路径
+pub fn write_exercise_log(log_dir: &Path, name: &str, phases: &[PhaseLog]) -> io::Result<PathBuf> {
+    fs::create_dir_all(log_dir)?;
+    let path = log_dir.join(format!("{}.log", name));
+
+    let mut content = String::new();
+    for phase in phases {
+        content.push_str(&format!(
+            "===== {} ({}) =====\n$ {}\n",
+            phase.phase,
+            if phase.success { "ok" } else { "failed" },
+            phase.command
+        ));
+        if let Some(kb) = phase.peak_rss_kb {
+            content.push_str(&format!("peak memory: {} KB\n", kb));
+        }
+        content.push_str("----- stdout -----\n");
+        content.push_str(&phase.stdout);
+        content.push_str("\n----- stderr -----\n");
+        content.push_str(&phase.stderr);
+        content.push('\n');
+        if let Some(note) = &phase.note {
+            content.push_str("----- note -----\n");
+            content.push_str(note);
+            content.push('\n');
+        }
+    }
+
+    fs::write(&path, content)?;
+    Ok(path)
+}
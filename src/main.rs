@@ -1,257 +1,2321 @@
-use serde::{Deserialize, Serialize};
+mod benchmark;
+mod cache;
+mod cli;
+mod compile;
+mod differential;
+mod format;
+mod formats;
+mod hints;
+mod judge;
+mod lint;
+mod logs;
+mod marker;
+mod memory;
+mod process;
+mod reset;
+mod scaffold;
+mod scoring;
+mod tamper;
+mod validate;
+mod watch;
+
+use benchmark::{BenchmarkResult, BenchmarkSpec, BENCHMARK_BUILD_PHASE, BENCHMARK_PHASE};
+use cache::{environment_fingerprint, exercise_hash, ResultCache};
+use cli::{CliCommand, Options, USAGE};
+use compile::{OptLevel, WarningPolicy};
+use differential::{DifferentialSpec, DIFFERENTIAL_PHASE};
+use format::{FileDiff, FormatPolicy, FORMAT_PHASE};
+use judge::{CaseResult, CaseVerdict, CompareMode};
+use lint::{ClippyPolicy, Lint, CLIPPY_PHASE};
+use logs::{write_exercise_log, PhaseLog};
+use process::{run_with_deadline, run_with_stdin, CommandOutcome, Usage};
+use scoring::{parse_libtest_counts, partial_fraction, ScoringMode};
+use tamper::{TestManifest, TEST_MANIFEST};
+use colored::{Color, Colorize};
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
 use std::process::{Command, exit};
-use std::time::Instant;
-use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::io;
+
+// 所有习题所在的根目录
+const EXERCISES_DIR: &str = "exercises";
+
+// 评测过程中产生的中间文件（如单文件习题的测试二进制）统一放在这里
+const GRADER_DIR: &str = "target/grader";
+
+// 按内容哈希缓存的评测结果
+const CACHE_FILE: &str = "target/grader/cache.json";
+
+// 所有 cargo 项目习题共用的构建目录，避免每道题都从头编译
+const CARGO_TARGET_DIR: &str = "target/grader/cargo-target";
+
+// 各习题的完整编译、测试输出日志所在目录名，与 report.json 放在一起
+const LOG_DIR: &str = "logs";
+
+// 未指定 --hidden-tests 时从该环境变量读取隐藏测试所在的目录；隐藏测试不放在仓库中，
+// 配置中的 `hidden_tests` 相对于该目录
+const HIDDEN_TESTS_ENV: &str = "GRADER_HIDDEN_TESTS_DIR";
+
+// 隐藏测试阶段在日志中的名称
+const HIDDEN_PHASE: &str = "hidden";
 
+// cargo 项目习题注入测试套件的副本在 target/grader/<习题名>/ 下的目录名
+const TEST_PROJECT_DIR: &str = "test-project";
+
+// 单道习题（编译、测试、clippy 全部阶段）的默认墙钟时间上限，可在配置中用 `timeout_secs` 覆盖
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+// 评测结束后清理习题构建产物（cargo clean -p）的时间上限
+const CLEAN_TIMEOUT_SECS: u64 = 60;
+
+// 未知字段（如 `timeout_sec` 这样的拼写错误）使配置无法加载，而不是被悄悄忽略
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 struct Exercise {
     name: String,
     path: String,
     #[serde(rename = "type")]
-    exercise_type: String,  
-    score: i32, 
+    exercise_type: String,
+    // 省略时取所在难度的 default_score，加载配置后总是有值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    score: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "ScoringMode::is_all_or_nothing")]
+    scoring: ScoringMode,
+    // 提示：`hint` 为单条提示，`hints` 为按顺序逐步给出的多条提示
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hints: Vec<String>,
+    // 评测时额外运行的隐藏测试文件，学生可见的测试之外单独报告
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hidden_tests: Option<String>,
+    // 与参考实现比较的随机差分测试
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    differential: Option<DifferentialSpec>,
+    // io_judge 习题比较输出的方式及浮点比较的误差
+    #[serde(default, skip_serializing_if = "CompareMode::is_whitespace")]
+    compare: CompareMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    float_tolerance: Option<f64>,
+    // 以 release 模式多次运行测试并与时间预算比较
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    benchmark: Option<BenchmarkSpec>,
+    // 测试进程可用的内存上限（MB），超出即不通过
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memory_limit_mb: Option<u64>,
+    // 单文件习题的编译选项：edition（默认 2021）、优化级别、额外的 rustc 参数与编译警告的处理方式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    edition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opt_level: Option<OptLevel>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rustc_flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "WarningPolicy::is_warn")]
+    warnings: WarningPolicy,
+    // clippy lint 的处理方式：忽略、报告、按条扣分或不予通过
+    #[serde(default, skip_serializing_if = "ClippyPolicy::is_warn")]
+    clippy: ClippyPolicy,
+    // rustfmt 格式检查的处理方式，省略时取所在难度的 format_check；都没有时不检查
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format_check: Option<FormatPolicy>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Exercise {
+    // 习题在 exercises/ 下的实际路径（单文件或 cargo 项目目录）
+    fn full_path(&self) -> PathBuf {
+        PathBuf::from(&format!("./{}/{}", EXERCISES_DIR, self.path))
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    fn score(&self) -> i32 {
+        self.score.unwrap_or(0)
+    }
+
+    fn float_tolerance(&self) -> f64 {
+        self.float_tolerance.unwrap_or(judge::DEFAULT_FLOAT_TOLERANCE)
+    }
+
+    fn hidden_tests_path(&self, hidden_tests_dir: &Path) -> Option<PathBuf> {
+        self.hidden_tests.as_ref().map(|path| hidden_tests_dir.join(path))
+    }
+
+    fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or(compile::DEFAULT_EDITION)
+    }
+
+    // 单文件习题编译测试程序与性能评测程序共用的 rustc 参数：edition 与配置中的额外参数
+    fn rustc_args(&self) -> Vec<String> {
+        let mut args = vec!["--edition".to_string(), self.edition().to_string()];
+        args.extend(self.rustc_flags.iter().cloned());
+        args
+    }
+}
+
+// 一个难度及其习题；除习题列表外的字段均可省略
+#[derive(Debug)]
+struct Tier {
+    name: String,
+    // 控制台中显示难度名所用的颜色，如 "green"、"bright blue"
+    color: Option<String>,
+    default_score: Option<i32>,
+    format_check: Option<FormatPolicy>,
+    exercises: Vec<Exercise>,
+}
+
+impl Tier {
+    fn display_name(&self) -> String {
+        match self.color.as_deref().map(str::parse::<Color>) {
+            Some(Ok(color)) => self.name.color(color).to_string(),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+// 配置文件中一个难度的值：习题数组（旧格式），或带元数据的对象
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TierSpec {
+    Exercises(Vec<Exercise>),
+    Detailed(DetailedTier),
+}
+
+// 带元数据的难度；`expected_total` 只由 validate 检查
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DetailedTier {
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    default_score: Option<i32>,
+    #[serde(default)]
+    expected_total: Option<i32>,
+    #[serde(default)]
+    format_check: Option<FormatPolicy>,
+    exercises: Vec<Exercise>,
+}
+
+impl TierSpec {
+    fn into_tier(self, name: String) -> Tier {
+        let mut tier = match self {
+            TierSpec::Exercises(exercises) => {
+                Tier { name, color: None, default_score: None, format_check: None, exercises }
+            }
+            TierSpec::Detailed(DetailedTier { color, default_score, format_check, exercises, .. }) => {
+                Tier { name, color, default_score, format_check, exercises }
+            }
+        };
+        for exercise in &mut tier.exercises {
+            exercise.score = exercise.score.or(tier.default_score);
+            exercise.format_check = exercise.format_check.or(tier.format_check);
+        }
+        tier
+    }
+}
+
+// 难度名到习题列表的有序映射，顺序即评测与报告的顺序
+#[derive(Debug)]
 struct ExerciseConfig {
-    easy: Vec<Exercise>,
-    normal: Vec<Exercise>,
-    hard: Vec<Exercise>,
+    tiers: Vec<Tier>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl ExerciseConfig {
+    fn tier(&self, name: &str) -> Option<&Tier> {
+        self.tiers.iter().find(|tier| tier.name == name)
+    }
+
+    // 按配置顺序遍历 (难度, 习题)
+    fn exercises(&self) -> impl Iterator<Item = (&Tier, &Exercise)> {
+        self.tiers.iter().flat_map(|tier| tier.exercises.iter().map(move |exercise| (tier, exercise)))
+    }
+}
+
+impl<'de> Deserialize<'de> for ExerciseConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TiersVisitor;
+
+        impl<'de> Visitor<'de> for TiersVisitor {
+            type Value = ExerciseConfig;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object mapping difficulty names to exercises")
+            }
+
+            // 按文件中出现的顺序保留难度
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ExerciseConfig, A::Error> {
+                let mut tiers = Vec::new();
+                while let Some((name, spec)) = map.next_entry::<String, TierSpec>()? {
+                    tiers.push(spec.into_tier(name));
+                }
+                Ok(ExerciseConfig { tiers })
+            }
+        }
+
+        deserializer.deserialize_map(TiersVisitor)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "StoredExerciseResult")]
 struct ExerciseResult {
     name: String,
+    // 所属难度；旧版报告中没有该字段时为空
+    #[serde(skip_serializing_if = "String::is_empty")]
+    tier: String,
+    status: ExerciseStatus,
+    // 兼容旧版报告：仅当 status 为 pass 时为 true
     result: bool,
-    score: i32, 
+    score: i32,
+    // 按习题计分方式得到的得分比例与实际得分；all_or_nothing 下 points 与 score 相同
+    fraction: f64,
+    points: f64,
+    // 评测耗时（毫秒）
+    duration_ms: u64,
+    // 未通过时失败阶段的输出摘要
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    // 完整的编译、测试、clippy 输出日志
+    #[serde(skip_serializing_if = "Option::is_none")]
+    log_path: Option<String>,
+    // 隐藏测试的通过情况，仅在配置了隐藏测试且已运行时存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hidden_tests: Option<HiddenTestsResult>,
+    // io_judge 习题各用例的结果
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cases: Vec<CaseResult>,
+    // 性能评测的计时结果，仅在配置了 benchmark 且测试全部通过时存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    benchmark: Option<BenchmarkResult>,
+    // 各测试进程峰值常驻内存中的最大值（KB），未运行测试时不存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    peak_memory_kb: Option<u64>,
+    // 单文件习题编译时的警告
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    // clippy 报告的 lint，以及按 `clippy: {"deduct": N}` 扣除的分数
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    lints: Vec<Lint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lint_deduction: Option<i32>,
+    // 格式不符合 rustfmt 的文件，以及按 `format_check: {"deduct": N}` 扣除的分数
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unformatted_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format_deduction: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct HiddenTestsResult {
+    passed: usize,
+    failed: usize,
+}
+
+// report.json 中读回的条目，旧版报告只有 name/result/score 三个字段
+#[derive(Deserialize)]
+struct StoredExerciseResult {
+    name: String,
+    #[serde(default)]
+    tier: String,
+    #[serde(default)]
+    status: Option<ExerciseStatus>,
+    result: bool,
+    score: i32,
+    #[serde(default)]
+    fraction: Option<f64>,
+    #[serde(default)]
+    points: Option<f64>,
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    log_path: Option<String>,
+    #[serde(default)]
+    hidden_tests: Option<HiddenTestsResult>,
+    #[serde(default)]
+    cases: Vec<CaseResult>,
+    #[serde(default)]
+    benchmark: Option<BenchmarkResult>,
+    #[serde(default)]
+    peak_memory_kb: Option<u64>,
+    #[serde(default)]
+    warnings: Vec<String>,
+    #[serde(default)]
+    lints: Vec<Lint>,
+    #[serde(default)]
+    lint_deduction: Option<i32>,
+    #[serde(default)]
+    unformatted_files: Vec<String>,
+    #[serde(default)]
+    format_deduction: Option<i32>,
+}
+
+impl From<StoredExerciseResult> for ExerciseResult {
+    fn from(stored: StoredExerciseResult) -> Self {
+        // 旧版报告无法区分失败原因，统一视为测试失败
+        let status = stored.status.unwrap_or(if stored.result {
+            ExerciseStatus::Pass
+        } else {
+            ExerciseStatus::TestFailure
+        });
+        ExerciseResult {
+            name: stored.name,
+            tier: stored.tier,
+            status,
+            result: stored.result,
+            score: stored.score,
+            fraction: stored.fraction.unwrap_or(if stored.result { 1.0 } else { 0.0 }),
+            points: stored.points.unwrap_or(stored.score as f64),
+            duration_ms: stored.duration_ms,
+            message: stored.message,
+            log_path: stored.log_path,
+            hidden_tests: stored.hidden_tests,
+            cases: stored.cases,
+            benchmark: stored.benchmark,
+            peak_memory_kb: stored.peak_memory_kb,
+            warnings: stored.warnings,
+            lints: stored.lints,
+            lint_deduction: stored.lint_deduction,
+            unformatted_files: stored.unformatted_files,
+            format_deduction: stored.format_deduction,
+        }
+    }
+}
+
+// 单道习题的评测结论，区分学生代码问题与评测环境问题
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum ExerciseStatus {
+    Pass,
+    CompileError,
+    TestFailure,
+    ClippyFailure,
+    Timeout,
+    // 评测器自身或工具链的问题，如 rustc/cargo 无法启动、配置中的未知类型
+    InfraError,
+    // 全部阶段均通过，但源码中仍带有 `// I AM NOT DONE` 标记
+    Pending,
+    // 测试与 test_manifest.json 中记录的官方测试不一致，不予评测
+    TestsModified,
+    // 学生可见的测试全部通过，但隐藏测试失败
+    HiddenTestFailure,
+    // 测试全部通过，但在随机输入上与参考实现的输出不一致
+    DifferentialFailure,
+    // 测试全部通过，但 release 模式下运行时间的中位数超出时间预算
+    OverBudget,
+    // 测试进程的内存占用超出 memory_limit_mb
+    MemoryLimitExceeded,
+}
+
+impl ExerciseStatus {
+    fn label(self) -> &'static str {
+        match self {
+            ExerciseStatus::Pass => "PASSED",
+            ExerciseStatus::CompileError => "COMPILATION FAILED",
+            ExerciseStatus::TestFailure => "TEST FAILED",
+            ExerciseStatus::ClippyFailure => "CLIPPY FAILED",
+            ExerciseStatus::Timeout => "TIMED OUT",
+            ExerciseStatus::InfraError => "INFRASTRUCTURE ERROR",
+            ExerciseStatus::Pending => "PENDING (I AM NOT DONE)",
+            ExerciseStatus::TestsModified => "TESTS MODIFIED",
+            ExerciseStatus::HiddenTestFailure => "HIDDEN TESTS FAILED",
+            ExerciseStatus::DifferentialFailure => "DIFFERENTIAL TEST FAILED",
+            ExerciseStatus::OverBudget => "OVER TIME BUDGET",
+            ExerciseStatus::MemoryLimitExceeded => "MEMORY LIMIT EXCEEDED",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 struct Statistics {
     total_exercises: usize,
     total_successes: usize,
     total_failures: usize,
-    total_score: i32,  
+    #[serde(default)]
+    total_compile_errors: usize,
+    #[serde(default)]
+    total_test_failures: usize,
+    #[serde(default)]
+    total_clippy_failures: usize,
+    #[serde(default)]
+    total_timeouts: usize,
+    #[serde(default)]
+    total_infra_errors: usize,
+    #[serde(default)]
+    total_tests_modified: usize,
+    #[serde(default)]
+    total_hidden_test_failures: usize,
+    #[serde(default)]
+    total_differential_failures: usize,
+    #[serde(default)]
+    total_over_budget: usize,
+    #[serde(default)]
+    total_memory_limit_exceeded: usize,
+    #[serde(default)]
+    total_pending: usize,
+    total_score: i32,
+    // 计入部分分后的总得分
+    #[serde(default)]
+    total_points: f64,
     total_time: u64,
 }
 
+impl Statistics {
+    fn from_results(results: &[ExerciseResult], total_time: u64) -> Self {
+        let mut statistics = Statistics { total_time, ..Statistics::default() };
+        for result in results {
+            statistics.record(result.status);
+            statistics.total_score += result.score;
+            statistics.total_points += result.points;
+        }
+        statistics.total_exercises = results.len();
+        statistics.total_points = (statistics.total_points * 100.0).round() / 100.0;
+        statistics
+    }
+
+    fn record(&mut self, status: ExerciseStatus) {
+        match status {
+            ExerciseStatus::Pass => self.total_successes += 1,
+            ExerciseStatus::CompileError => self.total_compile_errors += 1,
+            ExerciseStatus::TestFailure => self.total_test_failures += 1,
+            ExerciseStatus::ClippyFailure => self.total_clippy_failures += 1,
+            ExerciseStatus::Timeout => self.total_timeouts += 1,
+            ExerciseStatus::InfraError => self.total_infra_errors += 1,
+            ExerciseStatus::Pending => self.total_pending += 1,
+            ExerciseStatus::TestsModified => self.total_tests_modified += 1,
+            ExerciseStatus::HiddenTestFailure => self.total_hidden_test_failures += 1,
+            ExerciseStatus::DifferentialFailure => self.total_differential_failures += 1,
+            ExerciseStatus::OverBudget => self.total_over_budget += 1,
+            ExerciseStatus::MemoryLimitExceeded => self.total_memory_limit_exceeded += 1,
+        }
+        // pending 单独统计，不计入失败
+        if !matches!(status, ExerciseStatus::Pass | ExerciseStatus::Pending) {
+            self.total_failures += 1;
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct Report {
     exercises: Vec<ExerciseResult>,
     statistics: Statistics,
+    // 按配置顺序的各难度小计
+    #[serde(default)]
+    tiers: Vec<TierSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TierSummary {
+    name: String,
+    total_exercises: usize,
+    total_successes: usize,
+    total_score: i32,
+    // 该难度全部习题的满分之和
+    max_score: i32,
+    total_points: f64,
+}
+
+impl TierSummary {
+    // 按配置中的难度汇总报告条目；报告中没有记录的习题只计入满分
+    fn from_config(config: &ExerciseConfig, results: &[ExerciseResult]) -> Vec<Self> {
+        config
+            .tiers
+            .iter()
+            .map(|tier| {
+                let mut summary = TierSummary {
+                    name: tier.name.clone(),
+                    total_exercises: tier.exercises.len(),
+                    total_successes: 0,
+                    total_score: 0,
+                    max_score: tier.exercises.iter().map(Exercise::score).sum(),
+                    total_points: 0.0,
+                };
+                for result in results.iter().filter(|r| tier.exercises.iter().any(|e| e.name == r.name)) {
+                    summary.total_successes += usize::from(result.result);
+                    summary.total_score += result.score;
+                    summary.total_points += result.points;
+                }
+                summary.total_points = (summary.total_points * 100.0).round() / 100.0;
+                summary
+            })
+            .collect()
+    }
+}
+
+// 单个习题的控制台输出缓冲，评测结束后一次性打印，保证并行时输出不交错
+#[derive(Default)]
+struct OutputBuffer {
+    lines: Vec<(bool, String)>,
+}
+
+impl OutputBuffer {
+    fn out(&mut self, line: impl Into<String>) {
+        self.lines.push((false, line.into()));
+    }
+
+    fn err(&mut self, line: impl Into<String>) {
+        self.lines.push((true, line.into()));
+    }
+
+    fn flush(self) {
+        for (is_err, line) in self.lines {
+            if is_err {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+// 一次评测运行中所有习题共用的设置
+struct Settings {
+    log_dir: PathBuf,
+    // 共享构建目录的绝对路径（cargo 在习题目录下运行）
+    cargo_target_dir: PathBuf,
+    clean_policy: CleanPolicy,
+    // 隐藏测试所在的目录（仓库之外），未指定时不运行隐藏测试
+    hidden_tests_dir: Option<PathBuf>,
+    // 共享构建目录同一时间只由一个 cargo 命令使用，见 EvalContext::lock_target_dir
+    target_dir_lock: Mutex<()>,
+    // 官方测试清单（仓库中没有时为 None）与是否恢复被修改的测试
+    test_manifest: Option<TestManifest>,
+    restore_tests: bool,
+    // 是否打印格式检查的差异等详细输出
+    verbose: bool,
+}
+
+impl Settings {
+    // 评测前检查习题的测试是否被修改，见 check_tests
+    fn check_tests(&self, exercise: &Exercise) -> Option<Evaluation> {
+        check_tests(self.test_manifest.as_ref()?, exercise, self.restore_tests)
+    }
+}
+
+// cargo 项目习题评测结束后如何清理其在共享构建目录中的产物
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CleanPolicy {
+    // 保留构建产物，下次评测可增量编译（默认）
+    #[default]
+    Keep,
+    // 仅在习题通过后清理，保留失败习题的产物便于重新评测
+    OnSuccess,
+    Always,
+}
+
+impl CleanPolicy {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "keep" => Ok(CleanPolicy::Keep),
+            "on-success" => Ok(CleanPolicy::OnSuccess),
+            "always" => Ok(CleanPolicy::Always),
+            _ => Err(format!("Invalid value for --clean: {} (expected keep, on-success or always)", value)),
+        }
+    }
 }
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Please provide a command: 'watch' or 'all'");
-        exit(1);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match cli::parse_args(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            exit(1);
+        }
+    };
+
+    match &cli.command {
+        CliCommand::Help => println!("{}", USAGE),
+        CliCommand::List => list_exercises(&cli.options),
+        CliCommand::Hint { name } => show_hint(name, &cli.options),
+        CliCommand::New { name } => new_exercise(name, &cli.options),
+        CliCommand::Reset { name } => reset_exercise(name, &cli.options),
+        CliCommand::Manifest => write_test_manifest(&cli.options),
+        CliCommand::Validate => {
+            if !check_config(&cli.options, true) {
+                exit(1);
+            }
+        }
+        CliCommand::Report => match load_report(&cli.options.report_path) {
+            Ok(mut report) => {
+                let config = load_exercise_config(&cli.options.config_path).ok();
+                // 旧版报告没有难度小计，按当前配置补算
+                if let (true, Some(config)) = (report.tiers.is_empty(), &config) {
+                    report.tiers = TierSummary::from_config(config, &report.exercises);
+                }
+                print_summary(&report, config.as_ref());
+                write_extra_formats(&report, config.as_ref(), &cli.options);
+            }
+            Err(e) => {
+                eprintln!("Failed to load report {}: {}", cli.options.report_path.display(), e);
+                exit(1);
+            }
+        },
+        CliCommand::All | CliCommand::Watch | CliCommand::Run { .. } => grade(&cli.command, &cli.options),
     }
+}
 
-    let mode = &args[1];
+// 评测选中的习题，打印汇总并写入报告；只评测了部分习题时与上一次的报告合并
+fn grade(command: &CliCommand, options: &Options) {
     let start_time = Instant::now();
 
-    
-    let config = match load_exercise_config("exercise_config.json") {
+    if !check_config(options, false) {
+        eprintln!("Refusing to grade with an invalid config; run `cargotest validate` for details.");
+        exit(1);
+    }
+    let config = load_config_or_exit(options);
+    let merge = options.is_filtered() || matches!(command, CliCommand::Run { .. });
+    let previous = if merge { load_report(&options.report_path).ok() } else { None };
+
+    let exercises = match select_exercises(command, options, &config, previous.as_ref()) {
+        Ok(exercises) => exercises,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+    if exercises.is_empty() {
+        println!("No exercises to grade.");
+        return;
+    }
+
+    let cargo_target_dir = match std::env::current_dir() {
+        Ok(dir) => dir.join(CARGO_TARGET_DIR),
+        Err(e) => {
+            eprintln!("Failed to resolve the shared build directory: {}", e);
+            exit(1);
+        }
+    };
+    let settings = Settings {
+        log_dir: options.report_path.parent().unwrap_or(Path::new("")).join(LOG_DIR),
+        cargo_target_dir,
+        clean_policy: options.clean_policy,
+        hidden_tests_dir: options
+            .hidden_tests_dir
+            .clone()
+            .or_else(|| std::env::var_os(HIDDEN_TESTS_ENV).map(PathBuf::from)),
+        target_dir_lock: Mutex::new(()),
+        test_manifest: load_test_manifest(),
+        restore_tests: options.restore_tests,
+        verbose: options.verbose,
+    };
+
+    // 内容、配置与工具链都未变化的习题直接复用缓存的结果；watch 模式总是重新评测
+    let is_watch = *command == CliCommand::Watch;
+    let mut cache = ResultCache::load(Path::new(CACHE_FILE));
+    let environment = environment_fingerprint();
+    let mut fresh: HashMap<String, ExerciseResult> = HashMap::new();
+    let mut pending: Vec<(Exercise, Option<String>)> = Vec::new();
+    for exercise in exercises {
+        // watch 模式在每次评测前自行检查测试，测试被修改的习题同样停下来显示其状态并等待修改
+        let tampered = if is_watch { None } else { settings.check_tests(&exercise) };
+        if let Some(evaluation) = tampered {
+            fresh.insert(exercise.name.clone(), exercise_result(&exercise, evaluation));
+            continue;
+        }
+        let hash = exercise_hash(&exercise, settings.hidden_tests_dir.as_deref(), &environment).ok();
+        let cached = hash
+            .as_deref()
+            .filter(|_| options.use_cache && !is_watch)
+            .and_then(|hash| cache.get(&exercise.name, hash));
+        match cached {
+            Some(result) => {
+                println!(
+                    "\nEvaluating {}: {} (cached: {})",
+                    exercise.exercise_type,
+                    exercise.name,
+                    result.status.label()
+                );
+                fresh.insert(exercise.name.clone(), result);
+            }
+            None => pending.push((exercise, hash)),
+        }
+    }
+    let to_evaluate: Vec<Exercise> = pending.iter().map(|(exercise, _)| exercise.clone()).collect();
+
+    // watch 模式需要停在未通过的习题上，只能串行评测
+    let evaluations = if is_watch {
+        watch::watch_exercises(&to_evaluate, &settings)
+    } else if options.jobs <= 1 {
+        evaluate_serially(&to_evaluate, &settings)
+    } else {
+        evaluate_in_parallel(options.jobs, &to_evaluate, &settings)
+    };
+
+    for ((exercise, hash), evaluation) in pending.into_iter().zip(evaluations) {
+        let result = exercise_result(&exercise, evaluation);
+        if let Some(hash) = hash.filter(|_| !is_watch) {
+            cache.insert(&exercise.name, hash, &result);
+        }
+        fresh.insert(exercise.name.clone(), result);
+    }
+    if let Err(e) = cache.save() {
+        eprintln!("Failed to save result cache: {}", e);
+    }
+    let mut previous: HashMap<String, ExerciseResult> = previous
+        .map(|report| report.exercises.into_iter().map(|r| (r.name.clone(), r)).collect())
+        .unwrap_or_default();
+
+    // 报告始终按配置顺序排列；本次未评测的习题沿用上一次的结果
+    let results: Vec<ExerciseResult> = config
+        .exercises()
+        .filter_map(|(tier, exercise)| {
+            let mut result = fresh.remove(&exercise.name).or_else(|| previous.remove(&exercise.name))?;
+            result.tier = tier.name.clone();
+            Some(result)
+        })
+        .collect();
+
+    let statistics = Statistics::from_results(&results, start_time.elapsed().as_secs());
+    let tiers = TierSummary::from_config(&config, &results);
+    let report = Report { exercises: results, statistics, tiers };
+
+    print_summary(&report, Some(&config));
+
+    if let Err(e) = save_report_to_json(&options.report_path, &report) {
+        eprintln!("Error saving report: {}", e);
+    }
+    write_extra_formats(&report, Some(&config), options);
+}
+
+// 仓库中没有测试清单时不做篡改检查
+fn load_test_manifest() -> Option<TestManifest> {
+    let path = Path::new(TEST_MANIFEST);
+    if !path.exists() {
+        return None;
+    }
+    match TestManifest::load(path) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            eprintln!("{}; skipping the test tamper check", e);
+            None
+        }
+    }
+}
+
+// 检查习题的测试是否与官方测试一致；不一致时按 --restore-tests 恢复后照常评测，
+// 否则直接返回 tests_modified 的评测结果
+fn check_tests(manifest: &TestManifest, exercise: &Exercise, restore: bool) -> Option<Evaluation> {
+    let mut modified = manifest.modified_files(exercise);
+    if modified.is_empty() {
+        return None;
+    }
+    if restore {
+        match manifest.restore(exercise, &modified) {
+            Ok(()) => {
+                println!("\nRestored the official tests of {}: {}", exercise.name, modified.join(", "));
+                // 清单之外的测试文件（如 tests/ 下新增的文件）无法恢复，仍按测试被修改处理
+                modified = manifest.modified_files(exercise);
+                if modified.is_empty() {
+                    return None;
+                }
+            }
+            Err(e) => eprintln!("Failed to restore the official tests of {}: {}", exercise.name, e),
+        }
+    }
+
+    println!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
+    println!("\x1b[31m{}: {}\x1b[0m", exercise.full_path().display(), ExerciseStatus::TestsModified.label());
+    let message = format!(
+        "Tests differ from the official tests in {}: {} (use --restore-tests to restore them)",
+        TEST_MANIFEST,
+        modified.join(", ")
+    );
+    println!("    {}", message);
+    let evaluation = Evaluation {
+        status: ExerciseStatus::TestsModified,
+        fraction: 0.0,
+        duration_ms: 0,
+        message: Some(message),
+        log_path: None,
+        hidden_tests: None,
+        cases: Vec::new(),
+        benchmark: None,
+        peak_memory_kb: None,
+        warnings: Vec::new(),
+        lints: Vec::new(),
+        lint_deduction: 0,
+        unformatted_files: Vec::new(),
+        format_deduction: 0,
+    };
+    Some(evaluation)
+}
+
+// 按 git 版本中的官方测试重新生成 test_manifest.json
+fn write_test_manifest(options: &Options) {
+    let config = load_config_or_exit(options);
+    let result = tamper::build_manifest(&config, options.from_revision.as_deref())
+        .and_then(|manifest| manifest.save(Path::new(TEST_MANIFEST)).map(|()| manifest));
+    match result {
+        Ok(manifest) => println!(
+            "Wrote {} ({} exercises, official tests taken from {})",
+            TEST_MANIFEST,
+            manifest.exercises.len(),
+            options.from_revision.as_deref().unwrap_or("HEAD")
+        ),
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+// 按 --format 在 report.json 旁写出 JUnit / Markdown / HTML 报告
+fn write_extra_formats(report: &Report, config: Option<&ExerciseConfig>, options: &Options) {
+    if options.formats.is_empty() {
+        return;
+    }
+    let groups = group_by_level(report, config);
+    match formats::write_reports(report, &groups, &options.report_path, &options.formats) {
+        Ok(paths) => {
+            for path in paths {
+                println!("Report written: {}", path.display());
+            }
+        }
+        Err(e) => eprintln!("Error saving report formats: {}", e),
+    }
+}
+
+// 按难度分组报告条目；旧版报告没有记录难度时到配置中查找，仍找不到的归入 "other"
+fn group_by_level<'a>(report: &'a Report, config: Option<&ExerciseConfig>) -> Vec<(String, Vec<&'a ExerciseResult>)> {
+    let mut groups: Vec<(String, Vec<&ExerciseResult>)> = Vec::new();
+    for result in &report.exercises {
+        let level = if result.tier.is_empty() {
+            config
+                .and_then(|config| config.exercises().find(|(_, e)| e.name == result.name))
+                .map_or("other", |(tier, _)| tier.name.as_str())
+        } else {
+            result.tier.as_str()
+        };
+        match groups.iter_mut().find(|(name, _)| name == level) {
+            Some((_, results)) => results.push(result),
+            None => groups.push((level.to_string(), vec![result])),
+        }
+    }
+    groups
+}
+
+// 校验配置并打印发现的问题，返回配置是否可用于评测；verbose 时在没有问题时也给出提示
+fn check_config(options: &Options, verbose: bool) -> bool {
+    let problems = validate::validate_config(&options.config_path, Path::new(EXERCISES_DIR));
+    for problem in &problems {
+        eprintln!("{}", problem);
+    }
+
+    let valid = !validate::has_errors(&problems);
+    if verbose {
+        let errors = problems.iter().filter(|p| p.severity == validate::Severity::Error).count();
+        let warnings = problems.len() - errors;
+        if problems.is_empty() {
+            println!("{}: OK", options.config_path.display());
+        } else {
+            println!("{}: {} error(s), {} warning(s)", options.config_path.display(), errors, warnings);
+        }
+    }
+    valid
+}
+
+fn load_config_or_exit(options: &Options) -> ExerciseConfig {
+    match load_exercise_config(&options.config_path) {
         Ok(cfg) => cfg,
         Err(e) => {
-            eprintln!("Failed to load config file: {}", e);
+            eprintln!("Failed to load config file: {} (run `cargotest validate` for details)", e);
+            exit(1);
+        }
+    }
+}
+
+// 按命令和 --level / --only-failed 选出要评测的习题，保持配置顺序
+fn select_exercises(
+    command: &CliCommand,
+    options: &Options,
+    config: &ExerciseConfig,
+    previous: Option<&Report>,
+) -> Result<Vec<Exercise>, String> {
+    if let Some(level) = &options.level {
+        if config.tier(level).is_none() {
+            let names: Vec<&str> = config.tiers.iter().map(|tier| tier.name.as_str()).collect();
+            return Err(format!("Unknown level: {} (expected one of: {})", level, names.join(", ")));
+        }
+    }
+
+    if options.only_failed && previous.is_none() {
+        return Err(format!("--only-failed needs a previous report at {}", options.report_path.display()));
+    }
+    let passed: Vec<&str> = previous
+        .map(|report| report.exercises.iter().filter(|r| r.result).map(|r| r.name.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut selected: Vec<Exercise> = config
+        .exercises()
+        .filter(|(tier, _)| options.level.as_deref().is_none_or(|level| level == tier.name))
+        // 上一次报告中没有记录的习题也视为未通过
+        .filter(|(_, exercise)| !options.only_failed || !passed.contains(&exercise.name.as_str()))
+        .map(|(_, exercise)| exercise.clone())
+        .collect();
+
+    if let CliCommand::Run { name } = command {
+        let found = find_exercise(config, name).ok_or_else(|| format!("Unknown exercise: {}", name))?;
+        selected.retain(|exercise| exercise.name == found.name);
+    }
+
+    Ok(selected)
+}
+
+// 按名称查找习题，单文件习题可省略 `.rs` 后缀
+fn find_exercise<'a>(config: &'a ExerciseConfig, name: &str) -> Option<&'a Exercise> {
+    config
+        .exercises()
+        .map(|(_, exercise)| exercise)
+        .find(|exercise| exercise.name == name || exercise.name == format!("{}.rs", name))
+}
+
+// 列出习题及其在上一次报告中的结果
+fn list_exercises(options: &Options) {
+    let config = load_config_or_exit(options);
+    let previous = load_report(&options.report_path).ok();
+    let exercises = match select_exercises(&CliCommand::List, options, &config, previous.as_ref()) {
+        Ok(exercises) => exercises,
+        Err(e) => {
+            eprintln!("{}", e);
             exit(1);
         }
     };
 
-    let mut report = Report {
-        exercises: Vec::new(),
-        statistics: Statistics {
-            total_exercises: 0,
-            total_successes: 0,
-            total_failures: 0,
-            total_score: 0,
-            total_time: 0,
-        },
+    let width = config.tiers.iter().map(|tier| tier.name.len()).max().unwrap_or(0).max(8);
+    println!("{:<16} {:<width$} {:<14} {:>5}  Last result", "Name", "Level", "Type", "Score");
+    for (tier, exercise) in config.exercises().filter(|(_, e)| exercises.iter().any(|s| s.name == e.name)) {
+        let last = previous
+            .as_ref()
+            .and_then(|report| report.exercises.iter().find(|r| r.name == exercise.name))
+            .map_or("-", |r| r.status.label());
+        // 颜色转义序列不占显示宽度，按难度名本身的长度补齐
+        let padding = " ".repeat(width - tier.name.len());
+        println!(
+            "{:<16} {}{} {:<14} {:>5}  {}",
+            exercise.name,
+            tier.display_name(),
+            padding,
+            exercise.exercise_type,
+            exercise.score(),
+            last
+        );
+    }
+}
+
+// 按模板生成新习题并登记到配置中，随后检查配置（例如该难度的总分是否仍符合约定）
+fn new_exercise(name: &str, options: &Options) {
+    let (Some(exercise_type), Some(level)) = (&options.exercise_type, &options.level) else {
+        eprintln!("'new' requires --type <single_file|cargo_project|io_judge> and --level <level>");
+        exit(1);
+    };
+    let new = scaffold::NewExercise { name, exercise_type, level, score: options.score };
+    match scaffold::create_exercise(&options.config_path, &new) {
+        Ok(path) => {
+            println!("Created {}", path.display());
+            println!("Registered in {} under {}", options.config_path.display(), level);
+            // 打印登记后配置中的警告
+            if !check_config(options, false) {
+                exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+// 把习题恢复为初始代码，测试保持不变
+fn reset_exercise(name: &str, options: &Options) {
+    let config = load_config_or_exit(options);
+    let Some(exercise) = find_exercise(&config, name) else {
+        eprintln!("Unknown exercise: {}", name);
+        exit(1);
+    };
+    match reset::reset_exercise(exercise, options.from_revision.as_deref()) {
+        Ok(backup) => {
+            println!("Backed up {} to {}", exercise.full_path().display(), backup.display());
+            println!("Reset {} to its starting code", exercise.name);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+// 打印习题源码注释中的题目描述与全部提示
+fn show_hint(name: &str, options: &Options) {
+    let config = load_config_or_exit(options);
+    let Some(exercise) = find_exercise(&config, name) else {
+        eprintln!("Unknown exercise: {}", name);
+        exit(1);
     };
 
-    
-    evaluate_exercises_from_config(mode, config, &mut report);
+    println!("{} ({})", exercise.name, exercise.full_path().display());
+    match hints::read_statement(exercise) {
+        Ok(Some(statement)) if !statement.lines.is_empty() => {
+            println!();
+            for line in &statement.lines {
+                if line.is_empty() {
+                    println!();
+                } else {
+                    println!("    {}", line);
+                }
+            }
+        }
+        // normal/hard 的题目描述在与习题同目录的 PDF 中
+        Ok(_) => {
+            for document in hints::statement_documents(exercise) {
+                println!("\nProblem statement: {}", document.display());
+            }
+        }
+        Err(e) => eprintln!("Failed to read the statement of {}: {}", exercise.name, e),
+    }
 
-    
-    report.statistics.total_time = start_time.elapsed().as_secs();
-    report.statistics.total_exercises = report.statistics.total_successes + report.statistics.total_failures;
+    let hints = hints::exercise_hints(exercise);
+    println!();
+    if hints.is_empty() {
+        println!("No hints for this exercise.");
+    }
+    for (index, hint) in hints.iter().enumerate() {
+        println!("Hint {}/{}: {}", index + 1, hints.len(), hint);
+    }
+}
 
-    
+fn print_summary(report: &Report, config: Option<&ExerciseConfig>) {
+    let statistics = &report.statistics;
     println!("\nSummary:");
-    println!("Total exercises: {}", report.statistics.total_exercises);
-    println!("Total successes: {}", report.statistics.total_successes);
-    println!("Total failures: {}", report.statistics.total_failures);
-    println!("Total score: {}", report.statistics.total_score);
+    println!("Total exercises: {}", statistics.total_exercises);
+    println!("Total successes: {}", statistics.total_successes);
+    println!("Total failures: {}", statistics.total_failures);
+    println!("  Compile errors: {}", statistics.total_compile_errors);
+    println!("  Test failures: {}", statistics.total_test_failures);
+    println!("  Hidden test failures: {}", statistics.total_hidden_test_failures);
+    println!("  Differential test failures: {}", statistics.total_differential_failures);
+    println!("  Over time budget: {}", statistics.total_over_budget);
+    println!("  Memory limit exceeded: {}", statistics.total_memory_limit_exceeded);
+    println!("  Clippy failures: {}", statistics.total_clippy_failures);
+    println!("  Timeouts: {}", statistics.total_timeouts);
+    println!("  Infrastructure errors: {}", statistics.total_infra_errors);
+    println!("  Tests modified: {}", statistics.total_tests_modified);
+    println!("Total pending: {}", statistics.total_pending);
+    println!("Total score: {}", statistics.total_score);
+    println!("Total points: {:.2}", statistics.total_points);
 
-    
-    if let Err(e) = save_report_to_json("report.json", &report) {
-        eprintln!("Error saving report: {}", e);
+    if !report.tiers.is_empty() {
+        println!("\nBy level:");
+    }
+    for summary in &report.tiers {
+        let name = config
+            .and_then(|config| config.tier(&summary.name))
+            .map_or_else(|| summary.name.clone(), Tier::display_name);
+        println!(
+            "  {}: {}/{} passed, score {}/{}, points {:.2}",
+            name,
+            summary.total_successes,
+            summary.total_exercises,
+            summary.total_score,
+            summary.max_score,
+            summary.total_points
+        );
     }
 }
 
 
-fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, io::Error> {
+fn load_exercise_config(file_path: &Path) -> Result<ExerciseConfig, io::Error> {
     let file = File::open(file_path)?;
     let config: ExerciseConfig = serde_json::from_reader(file)?;
     Ok(config)
 }
 
+fn load_report(file_path: &Path) -> Result<Report, io::Error> {
+    let file = File::open(file_path)?;
+    let report: Report = serde_json::from_reader(file)?;
+    Ok(report)
+}
 
-fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &mut Report) {
-    let all_exercises = [config.easy, config.normal, config.hard].concat();
+fn exercise_result(exercise: &Exercise, evaluation: Evaluation) -> ExerciseResult {
+    let status = evaluation.status;
+    let result = status == ExerciseStatus::Pass;
+    let deduction = evaluation.lint_deduction + evaluation.format_deduction;
+    let score = if result { (exercise.score() - deduction).max(0) } else { 0 };
+    // 保留两位小数，避免报告中出现 4.800000000000001 这样的浮点噪声
+    let points = ((exercise.score() as f64 * evaluation.fraction - deduction as f64).max(0.0) * 100.0).round() / 100.0;
+    // 扣分后得分比例随之降低
+    let fraction = if deduction > 0 && exercise.score() > 0 {
+        points / exercise.score() as f64
+    } else {
+        evaluation.fraction
+    };
 
-    for exercise in all_exercises {
-        println!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
-        let result = evaluate_exercise(&exercise);
+    ExerciseResult {
+        name: exercise.name.clone(),
+        // 由调用方按配置填入
+        tier: String::new(),
+        status,
+        result,
+        score,
+        fraction,
+        points,
+        duration_ms: evaluation.duration_ms,
+        message: evaluation.message,
+        log_path: evaluation.log_path,
+        hidden_tests: evaluation.hidden_tests,
+        cases: evaluation.cases,
+        benchmark: evaluation.benchmark,
+        peak_memory_kb: evaluation.peak_memory_kb,
+        warnings: evaluation.warnings,
+        lints: evaluation.lints,
+        lint_deduction: (evaluation.lint_deduction > 0).then_some(evaluation.lint_deduction),
+        unformatted_files: evaluation.unformatted_files,
+        format_deduction: (evaluation.format_deduction > 0).then_some(evaluation.format_deduction),
+    }
+}
 
-        let score = if result { exercise.score } else { 0 };
+// 按配置顺序逐题评测
+fn evaluate_serially(exercises: &[Exercise], settings: &Settings) -> Vec<Evaluation> {
+    let mut results = Vec::new();
 
-        report.exercises.push(ExerciseResult {
-            name: exercise.name.clone(),
-            result,
-            score,
-        });
+    for exercise in exercises {
+        let mut out = OutputBuffer::default();
+        let result = evaluate_exercise(exercise, settings, &mut out);
+        out.flush();
+        results.push(result);
+    }
 
-        if result {
-            report.statistics.total_successes += 1;
-        } else {
-            report.statistics.total_failures += 1;
+    results
+}
+
+// 使用 `jobs` 个工作线程并行评测，每题的输出在该题结束后整体打印，结果按配置顺序返回；
+// 配置了 benchmark 的习题计时会受其他习题的负载干扰，等线程池全部结束后再逐个评测
+fn evaluate_in_parallel(jobs: usize, exercises: &[Exercise], settings: &Settings) -> Vec<Evaluation> {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    let mut results: Vec<Option<Evaluation>> = exercises.iter().map(|_| None).collect();
+    let (benchmarked, concurrent): (Vec<usize>, Vec<usize>) =
+        (0..exercises.len()).partition(|&index| exercises[index].benchmark.is_some());
+
+    thread::scope(|s| {
+        for _ in 0..jobs.min(concurrent.len()) {
+            let tx = tx.clone();
+            let next = &next;
+            let concurrent = &concurrent;
+            s.spawn(move || loop {
+                let position = next.fetch_add(1, Ordering::SeqCst);
+                let Some(&index) = concurrent.get(position) else {
+                    break;
+                };
+                let exercise = &exercises[index];
+                let mut out = OutputBuffer::default();
+                let result = evaluate_exercise(exercise, settings, &mut out);
+                if tx.send((index, result, out)).is_err() {
+                    break;
+                }
+            });
         }
+        drop(tx);
 
-        report.statistics.total_score += score;
+        for (index, result, out) in rx {
+            out.flush();
+            results[index] = Some(result);
+        }
+    });
 
-        if mode == "watch" && !ask_to_continue() {
-            break;
+    for index in benchmarked {
+        let mut out = OutputBuffer::default();
+        let result = evaluate_exercise(&exercises[index], settings, &mut out);
+        out.flush();
+        results[index] = Some(result);
+    }
+
+    results
+        .into_iter()
+        .map(|result| {
+            result.unwrap_or(Evaluation {
+                status: ExerciseStatus::InfraError,
+                fraction: 0.0,
+                duration_ms: 0,
+                message: None,
+                log_path: None,
+                hidden_tests: None,
+                cases: Vec::new(),
+                benchmark: None,
+                peak_memory_kb: None,
+                warnings: Vec::new(),
+                lints: Vec::new(),
+                lint_deduction: 0,
+                unformatted_files: Vec::new(),
+                format_deduction: 0,
+            })
+        })
+        .collect()
+}
+
+
+fn evaluate_exercise(exercise: &Exercise, settings: &Settings, out: &mut OutputBuffer) -> Evaluation {
+    out.out(format!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name));
+    let start_time = Instant::now();
+    let exercise_path = exercise.full_path();
+    let mut ctx = EvalContext {
+        settings,
+        exercise,
+        deadline: Instant::now() + exercise.timeout(),
+        out,
+        phases: Vec::new(),
+        cases: Vec::new(),
+        benchmark: None,
+        peak_memory_kb: None,
+        lints: Vec::new(),
+        format_diffs: Vec::new(),
+    };
+    let mut status = match grader_suites(exercise, settings.hidden_tests_dir.as_deref(), ctx.out) {
+        Err(e) => {
+            ctx.out.err(e);
+            ExerciseStatus::InfraError
+        }
+        Ok(suites) => match exercise.exercise_type.as_str() {
+            "single_file" => evaluate_single_file(&mut ctx, exercise, &exercise_path, &suites),
+            "cargo_project" => evaluate_cargo_project(&mut ctx, exercise, &exercise_path, &suites),
+            "io_judge" => evaluate_io_judge(&mut ctx, exercise, &exercise_path),
+            _ => {
+                ctx.out.err(format!("Unknown exercise type: {}", exercise.exercise_type));
+                ExerciseStatus::InfraError
+            }
+        },
+    };
+    // 格式检查不影响结论，代码无法编译时也没有意义
+    let checks_format = exercise.format_check.is_some_and(|policy| policy != FormatPolicy::Off);
+    if checks_format && !matches!(status, ExerciseStatus::CompileError | ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+        let format_status = check_format(&mut ctx, exercise, &exercise_path);
+        if status == ExerciseStatus::Pass {
+            status = format_status;
         }
     }
+    let EvalContext { out, mut phases, cases, benchmark, peak_memory_kb, lints, format_diffs, .. } = ctx;
+
+    // 通过但源码中仍带有未完成标记的习题记为 pending，等学生删除标记后才算完成
+    let mut pending_note = None;
+    let status = match status {
+        ExerciseStatus::Pass => match marker::files_with_marker(exercise) {
+            Ok(files) if files.is_empty() => status,
+            Ok(files) => {
+                let files: Vec<String> = files.iter().map(|file| file.display().to_string()).collect();
+                pending_note = Some(format!(
+                    "Remove the `// {}` comment from {} when you are done",
+                    marker::NOT_DONE_MARKER,
+                    files.join(", ")
+                ));
+                ExerciseStatus::Pending
+            }
+            Err(e) => {
+                out.err(format!("Failed to read sources of {}: {}", exercise.name, e));
+                ExerciseStatus::InfraError
+            }
+        },
+        _ => status,
+    };
+
+    match status {
+        ExerciseStatus::Pass => out.out(format!("\x1b[32m{}: {}\x1b[0m", exercise_path.display(), status.label())),
+        ExerciseStatus::Pending => out.out(format!("\x1b[33m{}: {}\x1b[0m", exercise_path.display(), status.label())),
+        ExerciseStatus::Timeout => out.out(format!(
+            "\x1b[31m{}: {} after {}s\x1b[0m",
+            exercise_path.display(),
+            status.label(),
+            exercise.timeout().as_secs()
+        )),
+        _ => out.out(format!("\x1b[31m{}: {}\x1b[0m", exercise_path.display(), status.label())),
+    }
+
+    if let Some(peak) = peak_memory_kb {
+        match exercise.memory_limit_mb {
+            Some(limit) => out.out(format!("Peak memory: {} (limit {} MB)", memory::format_kb(peak), limit)),
+            None => out.out(format!("Peak memory: {}", memory::format_kb(peak))),
+        }
+    }
+
+    let warnings: Vec<String> = phases
+        .iter()
+        .filter(|phase| phase.phase == "compile")
+        .flat_map(|phase| compile::warnings(&phase.stderr))
+        .collect();
+    if !warnings.is_empty() {
+        out.out(format!("Compiler warnings ({}):", warnings.len()));
+        for warning in &warnings {
+            out.out(format!("    {}", warning));
+        }
+    }
+
+    if !lints.is_empty() {
+        out.out(format!("Clippy lints ({}):", lints.len()));
+        for lint in &lints {
+            out.out(format!("    {}", lint.describe()));
+        }
+    }
+
+    if !format_diffs.is_empty() {
+        out.out(format!("Unformatted files ({}):", format_diffs.len()));
+        for diff in &format_diffs {
+            out.out(format!("    {}", diff.file));
+            if settings.verbose {
+                for line in diff.excerpt() {
+                    out.out(format!("        {}", line));
+                }
+            }
+        }
+        if !settings.verbose {
+            out.out("    (run with --verbose to see the differences, or run rustfmt to fix them)");
+        }
+    }
+    let unformatted_files: Vec<String> = format_diffs.into_iter().map(|diff| diff.file).collect();
+
+    let hidden_tests = phases
+        .iter()
+        .find(|phase| phase.phase == HIDDEN_PHASE)
+        .and_then(|phase| parse_libtest_counts(&phase.stdout))
+        .map(|(passed, failed)| HiddenTestsResult { passed, failed });
+    if let Some(hidden) = &hidden_tests {
+        out.out(format!("Hidden tests: {} passed, {} failed", hidden.passed, hidden.failed));
+    }
+
+    let fraction = earned_fraction(exercise.scoring, status, &phases, &cases);
+    if status != ExerciseStatus::Pass && fraction > 0.0 {
+        out.out(format!("Partial credit: {:.2}%", fraction * 100.0));
+    }
+    // 未得分的习题无分可扣
+    let lint_deduction = match exercise.clippy {
+        ClippyPolicy::Deduct(points) if fraction > 0.0 => points * lints.len() as i32,
+        _ => 0,
+    };
+    if lint_deduction > 0 {
+        out.out(format!("Clippy deduction: {} point(s) for {} lint(s)", lint_deduction, lints.len()));
+    }
+    let format_deduction = match exercise.format_check {
+        Some(FormatPolicy::Deduct(points)) if fraction > 0.0 => points * unformatted_files.len() as i32,
+        _ => 0,
+    };
+    if format_deduction > 0 {
+        out.out(format!(
+            "Format deduction: {} point(s) for {} unformatted file(s)",
+            format_deduction,
+            unformatted_files.len()
+        ));
+    }
+
+    // 失败时打印第一个失败阶段的输出摘要；隐藏测试的内容不向学生展示
+    let excerpt = phases.iter().find(|phase| !phase.success).map(|phase| {
+        if phase.phase != HIDDEN_PHASE {
+            return phase.excerpt();
+        }
+        match &hidden_tests {
+            Some(hidden) => vec![format!(
+                "{} of {} hidden tests failed (details are not shown)",
+                hidden.failed,
+                hidden.passed + hidden.failed
+            )],
+            None => vec!["Hidden tests failed to build or run (details are not shown)".to_string()],
+        }
+    });
+    for line in excerpt.iter().flatten() {
+        out.out(format!("    {}", line));
+    }
+    if let Some(note) = &pending_note {
+        out.out(format!("    {}", note));
+    }
+
+    // 日志对学生同样可见：隐藏测试与差分测试阶段只保留结论与通过数（摘要与 hidden_tests 已在上面取得）
+    for phase in phases.iter_mut().filter(|phase| [HIDDEN_PHASE, DIFFERENTIAL_PHASE].contains(&phase.phase.as_str())) {
+        let counts = parse_libtest_counts(&phase.stdout);
+        phase.redact(counts);
+    }
+
+    let log_path = if phases.is_empty() {
+        None
+    } else {
+        match write_exercise_log(&settings.log_dir, &exercise.name, &phases) {
+            Ok(path) => {
+                if !matches!(status, ExerciseStatus::Pass | ExerciseStatus::Pending) {
+                    out.out(format!("Full log: {}", path.display()));
+                }
+                Some(path.display().to_string())
+            }
+            Err(e) => {
+                out.err(format!("Failed to write log for {}: {}", exercise.name, e));
+                None
+            }
+        }
+    };
+
+    Evaluation {
+        status,
+        fraction,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+        message: excerpt
+            .map(|lines| lines.join("\n"))
+            .or(pending_note)
+            .or_else(|| benchmark.as_ref().filter(|b| !b.within_budget).map(BenchmarkResult::summary)),
+        log_path,
+        hidden_tests,
+        cases,
+        benchmark,
+        peak_memory_kb,
+        warnings,
+        lints,
+        lint_deduction,
+        unformatted_files,
+        format_deduction,
+    }
+}
+
+// 按计分方式计算得分比例：通过得满分；partial 模式下测试失败时按测试输出（io_judge 为通过的用例数）给部分分，
+// 其余失败不得分
+fn earned_fraction(scoring: ScoringMode, status: ExerciseStatus, phases: &[PhaseLog], cases: &[CaseResult]) -> f64 {
+    match (scoring, status) {
+        (_, ExerciseStatus::Pass) => 1.0,
+        (ScoringMode::Partial, ExerciseStatus::TestFailure | ExerciseStatus::MemoryLimitExceeded) if !cases.is_empty() => {
+            let accepted = cases.iter().filter(|case| case.verdict == CaseVerdict::Accepted).count();
+            accepted as f64 / cases.len() as f64
+        }
+        (ScoringMode::Partial, ExerciseStatus::TestFailure) => phases
+            .iter()
+            .find(|phase| phase.phase == "test")
+            .and_then(|phase| partial_fraction(&phase.stdout))
+            .unwrap_or(0.0),
+        // 可见测试全部通过时按可见、隐藏与差分测试合计的通过比例给分
+        (ScoringMode::Partial, ExerciseStatus::HiddenTestFailure | ExerciseStatus::DifferentialFailure) => {
+            let (passed, total) = phases
+                .iter()
+                .filter(|phase| ["test", HIDDEN_PHASE, DIFFERENTIAL_PHASE].contains(&phase.phase.as_str()))
+                .filter_map(|phase| parse_libtest_counts(&phase.stdout))
+                .fold((0, 0), |(passed, total), (p, f)| (passed + p, total + p + f));
+            if total == 0 { 0.0 } else { passed as f64 / total as f64 }
+        }
+        _ => 0.0,
+    }
 }
 
+// 单道习题的评测结果
+struct Evaluation {
+    status: ExerciseStatus,
+    fraction: f64,
+    duration_ms: u64,
+    message: Option<String>,
+    log_path: Option<String>,
+    hidden_tests: Option<HiddenTestsResult>,
+    cases: Vec<CaseResult>,
+    benchmark: Option<BenchmarkResult>,
+    peak_memory_kb: Option<u64>,
+    warnings: Vec<String>,
+    lints: Vec<Lint>,
+    // 按 clippy 策略应扣除的分数，在 exercise_result 中从得分里扣除
+    lint_deduction: i32,
+    unformatted_files: Vec<String>,
+    // 按 format_check 策略应扣除的分数，与 lint_deduction 一并扣除
+    format_deduction: i32,
+}
+
+// 单道习题评测过程中的上下文：全局设置、所评测的习题、截止时间、控制台输出、各阶段的完整日志，
+// 以及 io_judge 的用例结果、性能评测结果、测试进程的峰值内存、clippy lint 与格式检查的差异
+struct EvalContext<'a> {
+    settings: &'a Settings,
+    exercise: &'a Exercise,
+    deadline: Instant,
+    out: &'a mut OutputBuffer,
+    phases: Vec<PhaseLog>,
+    cases: Vec<CaseResult>,
+    benchmark: Option<BenchmarkResult>,
+    peak_memory_kb: Option<u64>,
+    lints: Vec<Lint>,
+    format_diffs: Vec<FileDiff>,
+}
+
+impl<'a> EvalContext<'a> {
+    // 使用共享构建目录的 cargo 命令先取得构建目录的使用权。cargo 本身在整个命令期间持有构建目录的文件锁，
+    // 并行评测时其余习题的 cargo 命令只能等待；在评测器内排队并把等待的时间加到截止时间上，
+    // 使习题不会因为等待其他习题的构建而超时
+    fn lock_target_dir(&mut self, command: &Command) -> Option<MutexGuard<'a, ()>> {
+        let uses_target_dir = command.get_envs().any(|(key, value)| key == "CARGO_TARGET_DIR" && value.is_some());
+        if !uses_target_dir {
+            return None;
+        }
+        let settings = self.settings;
+        let waiting_since = Instant::now();
+        let guard = settings.target_dir_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.deadline += waiting_since.elapsed();
+        Some(guard)
+    }
+
+    // 在截止时间内运行一个评测阶段并记录其输出，命令失败时返回 `failure`
+    fn run_phase(&mut self, phase: &str, command: &mut Command, failure: ExerciseStatus) -> ExerciseStatus {
+        let _target_dir = self.lock_target_dir(command);
+        let description = describe_command(command);
+        let result = run_with_deadline(command, self.deadline);
+        self.record_phase(phase, description, result, |_| None, failure)
+    }
+
+    // 同 run_phase，用于直接运行测试程序的阶段：按 memory_limit_mb 限制测试进程的内存并记录其峰值内存
+    fn run_test_phase(&mut self, phase: &str, command: &mut Command, failure: ExerciseStatus) -> ExerciseStatus {
+        if let Some(limit) = self.exercise.memory_limit_mb {
+            process::limit_memory(command, limit);
+        }
+        process::measure_memory(command);
+        let description = describe_command(command);
+        let result = run_with_deadline(command, self.deadline);
+        self.record_phase(phase, description, result, |usage| Some(usage.peak_rss_kb), failure)
+    }
+
+    // 运行 cargo 项目的测试：`command` 为 `cargo test ...`，只在以 `--no-run` 构建测试程序期间占用共享构建目录，
+    // 构建出的测试程序在锁外逐个直接运行（工作目录为 `dir`，与 cargo test 一致），一个测试程序失败后仍运行其余的。
+    // 统计与限制内存的只是测试程序本身，不包括 cargo 与 rustc；构建与各测试程序的输出合并为该阶段的一份日志
+    fn run_cargo_tests(&mut self, phase: &str, command: &mut Command, dir: &Path, failure: ExerciseStatus) -> ExerciseStatus {
+        command.args(["--no-run", "--message-format=json-render-diagnostics"]);
+        let first = self.phases.len();
+        let mut status = self.run_phase(phase, command, failure);
+        if status == ExerciseStatus::Pass {
+            let build = &mut self.phases[first];
+            let executables = benchmark::executables(&build.stdout);
+            // 构建消息只用于取得测试程序的路径，诊断已渲染到 stderr
+            build.stdout.clear();
+            for executable in executables {
+                let test_status = self.run_test_phase(phase, Command::new(&executable).current_dir(dir), failure);
+                if status == ExerciseStatus::Pass {
+                    status = test_status;
+                }
+                if matches!(test_status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+                    break;
+                }
+            }
+        }
+        let mut logs = self.phases.split_off(first).into_iter();
+        if let Some(mut merged) = logs.next() {
+            logs.for_each(|log| merged.append(log));
+            self.phases.push(merged);
+        }
+        status
+    }
+
+    // 记录一个阶段的输出与测得的峰值内存（`peak` 从子进程的资源占用中取得）；超出内存上限时阶段失败，
+    // 结论为 MemoryLimitExceeded
+    fn record_phase(
+        &mut self,
+        phase: &str,
+        description: String,
+        result: io::Result<(CommandOutcome, Usage)>,
+        peak: impl FnOnce(Usage) -> Option<u64>,
+        failure: ExerciseStatus,
+    ) -> ExerciseStatus {
+        let (outcome, usage) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.out.err(format!("Error executing {}: {}", description, e));
+                return ExerciseStatus::InfraError;
+            }
+        };
+        let peak_rss_kb = peak(usage);
+        self.peak_memory_kb = self.peak_memory_kb.max(peak_rss_kb);
+        let (output, timed_out) = match outcome {
+            CommandOutcome::Completed(output) => (output, false),
+            CommandOutcome::TimedOut(output) => (output, true),
+        };
+        let limit_note = self
+            .exercise
+            .memory_limit_mb
+            .zip(peak_rss_kb)
+            .filter(|&(limit, peak)| memory::exceeds_limit(limit, peak, &output))
+            .map(|(limit, peak)| memory::limit_note(limit, peak));
+        let over_limit = limit_note.is_some();
+        let success = !timed_out && !over_limit && output.status.success();
+        let mut log = PhaseLog::new(phase, description, success, &output);
+        log.peak_rss_kb = peak_rss_kb;
+        log.note = limit_note;
+        self.phases.push(log);
+        if timed_out {
+            ExerciseStatus::Timeout
+        } else if over_limit {
+            ExerciseStatus::MemoryLimitExceeded
+        } else if success {
+            ExerciseStatus::Pass
+        } else {
+            failure
+        }
+    }
+}
+
+fn describe_command(command: &Command) -> String {
+    let mut parts = vec![command.get_program().to_string_lossy().into_owned()];
+    parts.extend(command.get_args().map(|arg| arg.to_string_lossy().into_owned()));
+    parts.join(" ")
+}
+
+// 评测器注入习题的额外测试套件（隐藏测试、差分测试），在学生可见的测试之后单独运行
+struct TestSuite {
+    // 单文件习题中的模块名，也是 cargo 项目副本中的 test target 名
+    name: &'static str,
+    phase: &'static str,
+    failure: ExerciseStatus,
+    source: String,
+}
+
+impl TestSuite {
+    fn file_name(&self) -> String {
+        format!("__{}.rs", self.name)
+    }
+}
 
-fn evaluate_exercise(exercise: &Exercise) -> bool {
-    let exercise_path = PathBuf::from(&format!("./exercises/{}", exercise.path));
-    match exercise.exercise_type.as_str() {
-        "single_file" => evaluate_single_file(&exercise_path),
-        "cargo_project" => evaluate_cargo_project(&exercise_path),
-        _ => {
-            eprintln!("Unknown exercise type: {}", exercise.exercise_type);
-            false
+// 习题配置的额外测试套件。隐藏测试只在评测环境中通过 `hidden_tests_dir` 提供，未指定时跳过，
+// 指定了却找不到时按评测环境错误处理；参考实现本地没有对应文件时跳过
+fn grader_suites(exercise: &Exercise, hidden_tests_dir: Option<&Path>, out: &mut OutputBuffer) -> Result<Vec<TestSuite>, String> {
+    let read = |path: &Path| fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e));
+    let mut suites = Vec::new();
+    match (exercise.hidden_tests.as_ref(), hidden_tests_dir) {
+        (None, _) => {}
+        (Some(_), None) => out.out(format!(
+            "Hidden tests of {} are not available (use --hidden-tests or {}), skipping them",
+            exercise.name, HIDDEN_TESTS_ENV
+        )),
+        (Some(_), Some(dir)) if !dir.is_dir() => {
+            return Err(format!("Hidden tests directory {} does not exist", dir.display()));
+        }
+        (Some(_), Some(dir)) => {
+            let path = exercise.hidden_tests_path(dir).unwrap_or_default();
+            if !path.is_file() {
+                return Err(format!("Hidden tests {} of {} not found", path.display(), exercise.name));
+            }
+            suites.push(TestSuite {
+                name: "hidden_tests",
+                phase: HIDDEN_PHASE,
+                failure: ExerciseStatus::HiddenTestFailure,
+                source: read(&path)?,
+            });
+        }
+    }
+    if let Some(spec) = &exercise.differential {
+        let path = spec.reference_path();
+        if path.is_file() {
+            suites.push(TestSuite {
+                name: "differential_tests",
+                phase: DIFFERENTIAL_PHASE,
+                failure: ExerciseStatus::DifferentialFailure,
+                source: spec.test_module(&read(&path)?),
+            });
+        } else {
+            out.out(format!("Reference implementation {} not found, skipping differential tests", path.display()));
         }
     }
+    Ok(suites)
 }
 
-// 评测单文件 Rust 习题（使用 rustc --test 并执行测试）
-fn evaluate_single_file(file_path: &PathBuf) -> bool {
-    // 获取文件名（不带扩展名）
-    let test_binary = file_path.with_extension(""); 
+// 评测单文件 Rust 习题（使用 rustc --test 并执行测试）；额外测试套件作为模块追加到源码末尾，
+// 编译后先运行可见测试，再逐个运行各套件，全部通过且配置了 benchmark 时再进行性能评测
+fn evaluate_single_file(ctx: &mut EvalContext, exercise: &Exercise, file_path: &Path, suites: &[TestSuite]) -> ExerciseStatus {
+    let name = &exercise.name;
+    // 每道题使用独立的输出目录，避免并行评测时互相覆盖
+    let output_dir = PathBuf::from(GRADER_DIR).join(name);
+    if let Err(e) = fs::create_dir_all(&output_dir) {
+        ctx.out.err(format!("Failed to create output directory {}: {}", output_dir.display(), e));
+        return ExerciseStatus::InfraError;
+    }
+    let test_binary = output_dir.join(file_path.file_stem().unwrap_or_default());
+
+    let source = if suites.is_empty() {
+        file_path.to_path_buf()
+    } else {
+        match append_test_modules(file_path, suites, &output_dir) {
+            Ok(source) => source,
+            Err(e) => {
+                remove_test_modules(file_path, suites, &output_dir);
+                ctx.out.err(format!("Failed to prepare grader tests for {}: {}", name, e));
+                return ExerciseStatus::InfraError;
+            }
+        }
+    };
 
     // 编译测试文件
-    let compile_output = Command::new("rustc")
+    let mut compile = Command::new("rustc");
+    compile
         .arg("--test")  // 使用 rustc --test 进行编译
-        .arg(file_path)
+        .arg(&source)
         .arg("-o")
         .arg(&test_binary)  // 指定输出文件
-        .output();
-
-    if let Ok(output) = compile_output {
-        if output.status.success() {
-            // 编译成功，运行测试二进制文件
-            let test_output = Command::new(&test_binary)
-                .output();
-
-            let test_passed = match test_output {
-                Ok(test_run) => {
-                    if test_run.status.success() {
-                        println!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
-                        true
-                    } else {
-                        println!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
-                        false
+        .args(exercise.rustc_args());
+    if let Some(level) = &exercise.opt_level {
+        compile.arg(format!("-Copt-level={}", level));
+    }
+    if let Some(flag) = exercise.warnings.rustc_flag() {
+        compile.arg(flag);
+    }
+    // 编译的是追加了测试套件的副本时，诊断信息与 panic 位置中仍显示学生的源文件
+    if source != file_path {
+        compile.arg(format!("--remap-path-prefix={}={}", source.display(), file_path.display()));
+    }
+    let compile_status = ctx.run_phase("compile", &mut compile, ExerciseStatus::CompileError);
+    // 测试套件已编译进测试程序，不在输出目录中留下其源码
+    if source != file_path {
+        remove_test_modules(file_path, suites, &output_dir);
+    }
+    if compile_status != ExerciseStatus::Pass {
+        return compile_status;
+    }
+
+    // 编译成功，运行测试二进制文件
+    let mut visible = Command::new(&test_binary);
+    for suite in suites {
+        visible.args(["--skip", &format!("{}::", suite.name)]);
+    }
+    let mut status = ctx.run_test_phase("test", &mut visible, ExerciseStatus::TestFailure);
+    if !matches!(status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+        for suite in suites {
+            let suite_status = ctx.run_test_phase(
+                suite.phase,
+                Command::new(&test_binary).arg(format!("{}::", suite.name)),
+                suite.failure,
+            );
+            if status == ExerciseStatus::Pass {
+                status = suite_status;
+            }
+            if matches!(suite_status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+                break;
+            }
+        }
+    }
+
+    // 删除测试二进制文件
+    if let Err(e) = fs::remove_file(&test_binary) {
+        ctx.out.err(format!("Failed to remove test binary {}: {}", test_binary.display(), e));
+    } else {
+        ctx.out.out(format!("Successfully removed test binary: {}", test_binary.display()));
+    }
+
+    // clippy 只检查学生代码：以 lib 方式编译源文件，不带 --test，`#[cfg(test)]` 中的官方测试不参与
+    if exercise.clippy != ClippyPolicy::Ignore && !matches!(status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+        let clippy_dir = output_dir.join("clippy");
+        let clippy_status = run_clippy(
+            ctx,
+            Command::new("clippy-driver")
+                .args(["--crate-type", "lib", "--emit=metadata", "--error-format=json"])
+                .arg("--out-dir")
+                .arg(&clippy_dir)
+                .args(exercise.rustc_args())
+                .arg(file_path),
+            Path::new(""),
+        );
+        let _ = fs::remove_dir_all(&clippy_dir);
+        if status == ExerciseStatus::Pass {
+            status = clippy_status;
+        }
+    }
+
+    if let (ExerciseStatus::Pass, Some(spec)) = (status, &exercise.benchmark) {
+        status = benchmark_single_file(ctx, exercise, spec, file_path, &output_dir);
+    }
+
+    status
+}
+
+// 单文件习题的源码副本，末尾以 `#[path]` 引入各测试套件模块，返回副本路径
+fn append_test_modules(file_path: &Path, suites: &[TestSuite], output_dir: &Path) -> io::Result<PathBuf> {
+    let mut source = fs::read_to_string(file_path)?;
+    for suite in suites {
+        let module = output_dir.join(suite.file_name());
+        fs::write(&module, &suite.source)?;
+        source.push_str(&format!(
+            "\n#[cfg(test)]\n#[path = {:?}]\nmod {};\n",
+            fs::canonicalize(&module)?.display().to_string(),
+            suite.name
+        ));
+    }
+    let copy = output_dir.join(file_path.file_name().unwrap_or_default());
+    fs::write(&copy, source)?;
+    Ok(copy)
+}
+
+fn remove_test_modules(file_path: &Path, suites: &[TestSuite], output_dir: &Path) {
+    let copy = output_dir.join(file_path.file_name().unwrap_or_default());
+    for file in suites.iter().map(|suite| output_dir.join(suite.file_name())).chain([copy]) {
+        let _ = fs::remove_file(file);
+    }
+}
+
+// cargo 项目习题的副本，把各测试套件放在 src/ 下并在 Cargo.toml 中各追加一个 `[[test]]`，
+// 使其能像 src/tests.rs 一样通过 `mod` 引用习题模块；返回副本目录
+fn prepare_test_project(proj_path: &Path, suites: &[TestSuite], output_dir: &Path) -> io::Result<PathBuf> {
+    let copy = output_dir.join(TEST_PROJECT_DIR);
+    if copy.exists() {
+        fs::remove_dir_all(&copy)?;
+    }
+    reset::copy_dir(proj_path, &copy)?;
+
+    let mut manifest = fs::read_to_string(copy.join("Cargo.toml"))?;
+    for suite in suites {
+        fs::write(copy.join("src").join(suite.file_name()), &suite.source)?;
+        manifest.push_str(&format!("\n[[test]]\nname = \"{}\"\npath = \"src/{}\"\n", suite.name, suite.file_name()));
+    }
+    fs::write(copy.join("Cargo.toml"), manifest)?;
+    Ok(copy)
+}
+
+// 评测 Cargo 项目：依次执行 build、test、clippy，以第一个失败的阶段作为结论；
+// 构建成功且配置了额外测试套件时，再在副本中逐个运行；全部通过且配置了 benchmark 时再进行性能评测
+fn evaluate_cargo_project(ctx: &mut EvalContext, exercise: &Exercise, proj_path: &Path, suites: &[TestSuite]) -> ExerciseStatus {
+    let name = &exercise.name;
+    let phases = [("build", ExerciseStatus::CompileError), ("test", ExerciseStatus::TestFailure)];
+
+    let mut status = ExerciseStatus::Pass;
+    for (command, failure) in phases {
+        let mut cargo = Command::new("cargo");
+        cargo.arg(command).current_dir(proj_path).env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir);
+        let phase_status = if command == "test" {
+            ctx.run_cargo_tests(command, &mut cargo, proj_path, failure)
+        } else {
+            ctx.run_phase(command, &mut cargo, failure)
+        };
+        if status == ExerciseStatus::Pass {
+            status = phase_status;
+        }
+        // 超时或评测环境出错时后续阶段没有意义
+        if matches!(phase_status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+            break;
+        }
+    }
+
+    let mut built = !matches!(
+        status,
+        ExerciseStatus::CompileError | ExerciseStatus::Timeout | ExerciseStatus::InfraError
+    );
+    if built && exercise.clippy != ClippyPolicy::Ignore {
+        let clippy_status = run_clippy(
+            ctx,
+            Command::new("cargo")
+                .args(["clippy", "--message-format=json"])
+                .current_dir(proj_path)
+                .env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir),
+            proj_path,
+        );
+        if status == ExerciseStatus::Pass {
+            status = clippy_status;
+        }
+        built = !matches!(clippy_status, ExerciseStatus::Timeout | ExerciseStatus::InfraError);
+    }
+    if built && !suites.is_empty() {
+        let output_dir = PathBuf::from(GRADER_DIR).join(name);
+        match prepare_test_project(proj_path, suites, &output_dir) {
+            Ok(copy) => {
+                for suite in suites {
+                    let suite_status = ctx.run_cargo_tests(
+                        suite.phase,
+                        Command::new("cargo")
+                            .args(["test", "--test", suite.name])
+                            .current_dir(&copy)
+                            .env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir),
+                        &copy,
+                        suite.failure,
+                    );
+                    if status == ExerciseStatus::Pass {
+                        status = suite_status;
+                    }
+                    if matches!(suite_status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+                        break;
                     }
                 }
-                Err(_) => {
-                    eprintln!("Error running test executable for {}", file_path.display());
-                    false
-                }
-            };
+            }
+            Err(e) => {
+                ctx.out.err(format!("Failed to prepare grader tests for {}: {}", name, e));
+                status = ExerciseStatus::InfraError;
+            }
+        }
+        // 测试套件的副本只在评测期间存在
+        let _ = fs::remove_dir_all(output_dir.join(TEST_PROJECT_DIR));
+    }
 
-            // 删除测试二进制文件
-            if let Err(e) = fs::remove_file(&test_binary) {
-                eprintln!("Failed to remove test binary {}: {}", test_binary.display(), e);
-            } else {
-                println!("Successfully removed test binary: {}", test_binary.display());
+    if let (ExerciseStatus::Pass, Some(spec)) = (status, &exercise.benchmark) {
+        status = benchmark_cargo_project(ctx, spec, proj_path);
+    }
+
+    let clean = match ctx.settings.clean_policy {
+        CleanPolicy::Keep => false,
+        CleanPolicy::OnSuccess => status == ExerciseStatus::Pass,
+        CleanPolicy::Always => true,
+    };
+    if clean {
+        clean_build_artifacts(ctx, proj_path);
+    }
+
+    status
+}
+
+// 运行 clippy 并收集其中的 lint，日志中的 JSON 换成渲染后的诊断；有 error 级别的诊断（默认 deny 的 lint）时
+// 不论策略都记为 ClippyFailure，策略为 deny 时有任何 lint 即为 ClippyFailure，其余策略下 lint 不影响结论。
+// clippy 失败却没有报告任何错误说明它本身无法运行（如未安装），记为 InfraError
+fn run_clippy(ctx: &mut EvalContext, command: &mut Command, base: &Path) -> ExerciseStatus {
+    let status = ctx.run_phase(CLIPPY_PHASE, command, ExerciseStatus::ClippyFailure);
+    if matches!(status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+        return status;
+    }
+    let Some(log) = ctx.phases.last_mut() else {
+        return status;
+    };
+    // cargo 把诊断输出到 stdout，clippy-driver 输出到 stderr
+    let mut has_errors = false;
+    let lints = [&mut log.stdout, &mut log.stderr]
+        .into_iter()
+        .flat_map(|output| {
+            let lints = lint::clippy_lints(output, base);
+            has_errors |= lint::has_errors(output);
+            *output = lint::rendered(output);
+            lints
+        })
+        .collect::<Vec<_>>();
+    if !log.success && !has_errors {
+        ctx.out.err(format!("clippy failed to check {} (is clippy installed?)", ctx.exercise.name));
+        return ExerciseStatus::InfraError;
+    }
+    let denied = !lints.is_empty() && ctx.exercise.clippy == ClippyPolicy::Deny;
+    let failed = has_errors || denied;
+    log.success = !failed;
+    // lint 本身已在控制台与报告中列出，摘要只说明失败原因
+    if has_errors {
+        log.note = Some("clippy reported errors, which fail the exercise under any clippy policy".to_string());
+    } else if denied {
+        log.note = Some(format!("clippy reported {} lint(s), which this exercise does not allow", lints.len()));
+    }
+    ctx.lints.extend(lints);
+    if failed { ExerciseStatus::ClippyFailure } else { ExerciseStatus::Pass }
+}
+
+// 以 rustfmt --check 检查习题代码的格式，差异记入 ctx.format_diffs；只检查学生代码，不包括测试。
+// 单文件习题检查测试部分之前的代码，cargo 项目与 io_judge 习题用 cargo fmt 检查并略去测试文件
+fn check_format(ctx: &mut EvalContext, exercise: &Exercise, path: &Path) -> ExerciseStatus {
+    if exercise.exercise_type != "single_file" {
+        let project = match fs::canonicalize(path) {
+            Ok(project) => project,
+            Err(e) => {
+                ctx.out.err(format!("Failed to resolve {}: {}", path.display(), e));
+                return ExerciseStatus::InfraError;
             }
+        };
+        let status = ctx.run_phase(
+            FORMAT_PHASE,
+            Command::new("cargo").args(["fmt", "--check", "--", "--color", "never"]).current_dir(path),
+            ExerciseStatus::Pass,
+        );
+        // cargo fmt 报告的是绝对路径
+        return record_format_diffs(ctx, status, |file| {
+            let relative = Path::new(file).strip_prefix(&project).ok()?;
+            (!reset::is_test_file(path, relative)).then(|| path.join(relative).display().to_string())
+        });
+    }
 
-            return test_passed;
-        } else {
-            // 编译失败
-            eprintln!("\x1b[31m{}: COMPILATION FAILED\x1b[0m", file_path.display());
-            return false;
+    let format_dir = PathBuf::from(GRADER_DIR).join(&exercise.name).join("format");
+    let copy = fs::read_to_string(path).and_then(|source| {
+        fs::create_dir_all(&format_dir)?;
+        let copy = format_dir.join(path.file_name().unwrap_or_default());
+        fs::write(&copy, reset::split_tests(&source).0)?;
+        fs::canonicalize(&copy)
+    });
+    let copy = match copy {
+        Ok(copy) => copy,
+        Err(e) => {
+            ctx.out.err(format!("Failed to prepare the format check of {}: {}", exercise.name, e));
+            return ExerciseStatus::InfraError;
         }
-    } else {
-        eprintln!("Error executing rustc --test for {}", file_path.display());
-        return false;
+    };
+    let status = ctx.run_phase(
+        FORMAT_PHASE,
+        Command::new("rustfmt")
+            .args(["--check", "--color", "never", "--edition", exercise.edition()])
+            .arg(&copy),
+        ExerciseStatus::Pass,
+    );
+    let _ = fs::remove_dir_all(&format_dir);
+    // 日志与差异中显示学生的源文件而不是副本
+    if let Some(log) = ctx.phases.last_mut().filter(|log| log.phase == FORMAT_PHASE) {
+        log.stdout = log.stdout.replace(&copy.display().to_string(), &path.display().to_string());
     }
+    record_format_diffs(ctx, status, |file| Some(file.to_string()))
 }
 
-// 评测 Cargo 项目
-fn evaluate_cargo_project(proj_path: &PathBuf) -> bool {
-    let build_success = run_cargo_command(proj_path, "build");
-    let test_success = run_cargo_command(proj_path, "test");
-    let clippy_success = run_cargo_command(proj_path, "clippy");
+// 从格式检查阶段的输出中取出各文件的差异，`display` 给出显示的路径，返回 None 的文件（测试）不计入。
+// rustfmt 发现差异时以非零状态退出，这不算失败；没有差异却失败说明 rustfmt 本身无法运行，记为 InfraError
+fn record_format_diffs(ctx: &mut EvalContext, status: ExerciseStatus, display: impl Fn(&str) -> Option<String>) -> ExerciseStatus {
+    if matches!(status, ExerciseStatus::Timeout | ExerciseStatus::InfraError) {
+        return status;
+    }
+    let Some(log) = ctx.phases.last_mut() else {
+        return status;
+    };
+    let diffs = format::file_diffs(&log.stdout);
+    if !log.success && diffs.is_empty() {
+        ctx.out.err(format!("rustfmt failed to check {}", ctx.exercise.name));
+        return ExerciseStatus::InfraError;
+    }
+    log.success = true;
+    ctx.format_diffs.extend(
+        diffs
+            .into_iter()
+            .filter_map(|diff| Some(FileDiff { file: display(&diff.file)?, diff: diff.diff })),
+    );
+    ExerciseStatus::Pass
+}
 
-    let passed = build_success && test_success && clippy_success;
+// 评测 OJ 风格的习题：以 release 模式构建程序，逐个用例以 `.in` 为标准输入运行并按配置的方式与 `.out` 比较；
+// 全部用例通过才算通过，超时后不再运行剩余用例
+fn evaluate_io_judge(ctx: &mut EvalContext, exercise: &Exercise, proj_path: &Path) -> ExerciseStatus {
+    let cases = match judge::test_cases(proj_path) {
+        Ok(cases) if !cases.is_empty() => cases,
+        Ok(_) => {
+            ctx.out.err(format!("No test cases found in {}", proj_path.join(judge::CASES_DIR).display()));
+            return ExerciseStatus::InfraError;
+        }
+        Err(e) => {
+            ctx.out.err(format!("Failed to read test cases of {}: {}", exercise.name, e));
+            return ExerciseStatus::InfraError;
+        }
+    };
 
-    if passed {
-        println!("\x1b[32m{}: PASSED\x1b[0m", proj_path.display());
-    } else {
-        println!("\x1b[31m{}: FAILED\x1b[0m", proj_path.display());
+    let build_status = ctx.run_phase(
+        "build",
+        Command::new("cargo")
+            .args(["build", "--release"])
+            .current_dir(proj_path)
+            .env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir),
+        ExerciseStatus::CompileError,
+    );
+    if build_status != ExerciseStatus::Pass {
+        return build_status;
     }
+    let Some(package) = package_name(ctx, proj_path) else {
+        ctx.out.err(format!("Failed to determine the package name of {}", proj_path.display()));
+        return ExerciseStatus::InfraError;
+    };
+    let binary = ctx.settings.cargo_target_dir.join("release").join(package);
 
-    clean_target_directory(proj_path);
+    let mut status = ExerciseStatus::Pass;
+    for case in cases {
+        let (input, expected) = match (File::open(&case.input), fs::read_to_string(&case.expected)) {
+            (Ok(input), Ok(expected)) => (input, expected),
+            (Err(e), _) | (_, Err(e)) => {
+                ctx.out.err(format!("Failed to read test case {}: {}", case.name, e));
+                return ExerciseStatus::InfraError;
+            }
+        };
+        let mut command = Command::new(&binary);
+        let description = format!("{} < {}", describe_command(&command), case.input.display());
+        if let Some(limit) = exercise.memory_limit_mb {
+            process::limit_memory(&mut command, limit);
+        }
+        process::measure_memory(&mut command);
+        let started = Instant::now();
+        let (verdict, output, usage) = match run_with_stdin(&mut command, input.into(), ctx.deadline) {
+            Ok((CommandOutcome::Completed(output), usage)) if !output.status.success() => {
+                (CaseVerdict::RuntimeError, output, usage)
+            }
+            Ok((CommandOutcome::Completed(output), usage)) => (CaseVerdict::Accepted, output, usage),
+            Ok((CommandOutcome::TimedOut(output), usage)) => (CaseVerdict::Timeout, output, usage),
+            Err(e) => {
+                ctx.out.err(format!("Error executing {}: {}", description, e));
+                return ExerciseStatus::InfraError;
+            }
+        };
+        let duration_ms = started.elapsed().as_millis() as u64;
+        ctx.peak_memory_kb = ctx.peak_memory_kb.max(Some(usage.peak_rss_kb));
+        let limit_note = exercise
+            .memory_limit_mb
+            .filter(|&limit| verdict != CaseVerdict::Timeout && memory::exceeds_limit(limit, usage.peak_rss_kb, &output))
+            .map(|limit| memory::limit_note(limit, usage.peak_rss_kb));
+
+        let mut log = PhaseLog::new(&format!("case {}", case.name), description, false, &output);
+        log.peak_rss_kb = Some(usage.peak_rss_kb);
+        let verdict = match verdict {
+            _ if limit_note.is_some() => {
+                log.note = limit_note.map(|note| format!("case {}: {}", case.name, note));
+                CaseVerdict::MemoryLimitExceeded
+            }
+            CaseVerdict::Accepted => {
+                match judge::compare(exercise.compare, exercise.float_tolerance(), &expected, &log.stdout) {
+                    Ok(()) => CaseVerdict::Accepted,
+                    Err(difference) => {
+                        log.note = Some(format!("case {}: wrong answer: {}", case.name, difference));
+                        CaseVerdict::WrongAnswer
+                    }
+                }
+            }
+            CaseVerdict::RuntimeError => {
+                // panic 信息之后的回溯对学生意义不大
+                let stderr: Vec<&str> = log
+                    .stderr
+                    .lines()
+                    .take_while(|line| !line.starts_with("stack backtrace:") && !line.starts_with("note: run with"))
+                    .collect();
+                log.note = Some(format!("case {}: runtime error ({})\n{}", case.name, output.status, stderr.join("\n")));
+                verdict
+            }
+            _ => {
+                log.note = Some(format!("case {}: time limit exceeded", case.name));
+                verdict
+            }
+        };
+        log.success = verdict == CaseVerdict::Accepted;
+        ctx.phases.push(log);
+        ctx.out.out(format!("  case {}: {} ({} ms)", case.name, verdict.label(), duration_ms));
+        ctx.cases.push(CaseResult { name: case.name, verdict, duration_ms });
 
-    passed
+        match verdict {
+            CaseVerdict::Accepted => {}
+            CaseVerdict::Timeout => return ExerciseStatus::Timeout,
+            // 以第一个未通过的用例决定结论
+            _ if status != ExerciseStatus::Pass => {}
+            CaseVerdict::MemoryLimitExceeded => status = ExerciseStatus::MemoryLimitExceeded,
+            _ => status = ExerciseStatus::TestFailure,
+        }
+    }
+    status
 }
 
-// 运行 Cargo 命令
-fn run_cargo_command(proj_path: &PathBuf, command: &str) -> bool {
-    let output = Command::new("cargo")
-        .arg(command)
-        .current_dir(proj_path)
-        .output();
+// 以 `rustc --test -O` 构建单文件习题的 release 版测试程序并计时，结束后删除；
+// edition 与额外参数同编译测试时一致，优化级别固定为 -O
+fn benchmark_single_file(
+    ctx: &mut EvalContext,
+    exercise: &Exercise,
+    spec: &BenchmarkSpec,
+    file_path: &Path,
+    output_dir: &Path,
+) -> ExerciseStatus {
+    let stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+    let binary = output_dir.join(format!("{}-release", stem));
+    let build_status = ctx.run_phase(
+        BENCHMARK_BUILD_PHASE,
+        Command::new("rustc")
+            .args(["--test", "-O"])
+            .arg(file_path)
+            .arg("-o")
+            .arg(&binary)
+            .args(exercise.rustc_args()),
+        ExerciseStatus::CompileError,
+    );
+    if build_status != ExerciseStatus::Pass {
+        return build_status;
+    }
+    let status = time_runs(ctx, spec, std::slice::from_ref(&binary), None);
+    if let Err(e) = fs::remove_file(&binary) {
+        ctx.out.err(format!("Failed to remove test binary {}: {}", binary.display(), e));
+    }
+    status
+}
+
+// 以 release 模式构建 cargo 项目的测试（或 bench）目标并计时，测试程序在项目目录下运行（与 cargo test 一致）
+fn benchmark_cargo_project(ctx: &mut EvalContext, spec: &BenchmarkSpec, proj_path: &Path) -> ExerciseStatus {
+    let build_status = ctx.run_phase(
+        BENCHMARK_BUILD_PHASE,
+        Command::new("cargo")
+            .args(spec.cargo_args())
+            .args(["--no-run", "--message-format=json-render-diagnostics"])
+            .current_dir(proj_path)
+            .env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir),
+        ExerciseStatus::CompileError,
+    );
+    if build_status != ExerciseStatus::Pass {
+        return build_status;
+    }
+    let executables = ctx.phases.last().map(|phase| benchmark::executables(&phase.stdout)).unwrap_or_default();
+    if executables.is_empty() {
+        ctx.out.err(format!("No test or bench targets to benchmark in {}", proj_path.display()));
+        return ExerciseStatus::InfraError;
+    }
+    time_runs(ctx, spec, &executables, Some(proj_path))
+}
 
-    match output {
-        Ok(out) => out.status.success(),
-        Err(_) => false,
+// 依次运行全部测试程序记为一次计时，共 runs 次；任何一次失败即以该结论结束，否则按中位数与时间预算比较
+fn time_runs(ctx: &mut EvalContext, spec: &BenchmarkSpec, executables: &[PathBuf], dir: Option<&Path>) -> ExerciseStatus {
+    let mut samples = Vec::new();
+    for _ in 0..spec.runs {
+        let started = Instant::now();
+        for executable in executables {
+            let mut command = Command::new(executable);
+            if let Some(dir) = dir {
+                command.current_dir(dir);
+            }
+            let status = ctx.run_test_phase(BENCHMARK_PHASE, &mut command, ExerciseStatus::TestFailure);
+            if status != ExerciseStatus::Pass {
+                return status;
+            }
+        }
+        samples.push(started.elapsed());
     }
+
+    let result = BenchmarkResult::from_samples(spec, &samples);
+    ctx.out.out(result.summary());
+    let status = if result.within_budget { ExerciseStatus::Pass } else { ExerciseStatus::OverBudget };
+    ctx.benchmark = Some(result);
+    status
 }
 
-// 清理 target 目录
-fn clean_target_directory(proj_path: &PathBuf) {
-    let target_dir = proj_path.join("target");
+// 从共享构建目录中清理该习题自身的产物（`cargo clean -p`），不影响其他习题。清理在评测结束之后进行，
+// 不再受习题截止时间的限制，另有 CLEAN_TIMEOUT_SECS 的上限
+fn clean_build_artifacts(ctx: &mut EvalContext, proj_path: &Path) {
+    ctx.deadline = Instant::now() + Duration::from_secs(CLEAN_TIMEOUT_SECS);
+    let Some(package) = package_name(ctx, proj_path) else {
+        ctx.out.err(format!("Failed to determine the package name of {}", proj_path.display()));
+        return;
+    };
 
-    if target_dir.exists() {
-        if let Err(e) = fs::remove_dir_all(&target_dir) {
-            eprintln!("Failed to clean up target directory: {}", e);
-        } else {
-            println!("Successfully cleaned up target directory in: {}", proj_path.display());
+    let mut command = Command::new("cargo");
+    command
+        .args(["clean", "-p", &package])
+        .current_dir(proj_path)
+        .env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir);
+    let result = {
+        let _target_dir = ctx.lock_target_dir(&command);
+        run_with_deadline(&mut command, ctx.deadline)
+    };
+
+    match result {
+        Ok((CommandOutcome::Completed(output), _)) if output.status.success() => {
+            ctx.out.out(format!("Successfully cleaned up build artifacts of: {}", package));
         }
+        Ok((CommandOutcome::Completed(output), _)) => ctx.out.err(format!(
+            "Failed to clean up build artifacts of {}: {}",
+            package,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Ok((CommandOutcome::TimedOut(_), _)) => {
+            ctx.out.err(format!("Failed to clean up build artifacts of {}: timed out", package))
+        }
+        Err(e) => ctx.out.err(format!("Failed to clean up build artifacts of {}: {}", package, e)),
     }
 }
 
-// 用户确认是否继续
-fn ask_to_continue() -> bool {
-    let mut input = String::new();
-    println!("\nPress any key to continue, or 'q' to quit.");
-    io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_lowercase() != "q"
+// 通过 `cargo metadata` 读取习题 crate 的包名；与其他 cargo 命令一样先取得共享构建目录的使用权，
+// 超过截止时间时放弃
+fn package_name(ctx: &mut EvalContext, proj_path: &Path) -> Option<String> {
+    let mut command = Command::new("cargo");
+    command
+        .args(["metadata", "--no-deps", "--format-version", "1", "--offline"])
+        .current_dir(proj_path)
+        .env("CARGO_TARGET_DIR", &ctx.settings.cargo_target_dir);
+    let _target_dir = ctx.lock_target_dir(&command);
+    let (CommandOutcome::Completed(output), _) = run_with_deadline(&mut command, ctx.deadline).ok()? else {
+        return None;
+    };
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    metadata["packages"][0]["name"].as_str().map(str::to_string)
 }
 
 // 保存评测报告
-fn save_report_to_json(file_name: &str, report: &Report) -> io::Result<()> {
+fn save_report_to_json(file_name: &Path, report: &Report) -> io::Result<()> {
     let file = File::create(file_name)?;
     serde_json::to_writer_pretty(file, report)?;
     Ok(())
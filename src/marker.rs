@@ -0,0 +1,36 @@
+use super::cache::collect_files;
+use super::Exercise;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// rustlings 风格的未完成标记，学生完成习题后应删除这一行注释
+pub const NOT_DONE_MARKER: &str = "I AM NOT DONE";
+
+// 返回仍带有 `// I AM NOT DONE` 注释的源文件；cargo 项目检查除 target 外的全部 .rs 文件
+pub fn files_with_marker(exercise: &Exercise) -> io::Result<Vec<PathBuf>> {
+    let path = exercise.full_path();
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect_files(&path, &mut files)?;
+        files.retain(|file| file.extension().is_some_and(|ext| ext == "rs"));
+        files.sort();
+    } else {
+        files.push(path);
+    }
+
+    let mut marked = Vec::new();
+    for file in files {
+        if fs::read_to_string(&file)?.lines().any(is_marker_line) {
+            marked.push(file);
+        }
+    }
+    Ok(marked)
+}
+
+// 只认单独成行的行注释（`// I AM NOT DONE` 或 `//I AM NOT DONE`），不匹配字符串或其他注释中的同样文字
+pub fn is_marker_line(line: &str) -> bool {
+    line.trim()
+        .strip_prefix("//")
+        .is_some_and(|comment| comment.trim() == NOT_DONE_MARKER)
+}
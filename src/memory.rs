@@ -0,0 +1,30 @@
+use std::process::Output;
+
+// Rust 程序分配内存失败时中止前打印的信息：`memory allocation of N bytes failed`
+const ALLOCATION_FAILURE_PREFIX: &str = "memory allocation of ";
+const ALLOCATION_FAILURE_SUFFIX: &str = " failed";
+
+// 测试进程是否超出内存上限：峰值内存超过上限，或在上限处分配内存失败而中止
+// （RLIMIT_DATA 使分配在达到上限时失败，此时测得的峰值通常略低于上限）
+pub fn exceeds_limit(limit_mb: u64, peak_rss_kb: u64, output: &Output) -> bool {
+    if peak_rss_kb > limit_mb.saturating_mul(1024) {
+        return true;
+    }
+    !output.status.success()
+        && String::from_utf8_lossy(&output.stderr).lines().any(|line| {
+            let line = line.trim();
+            line.starts_with(ALLOCATION_FAILURE_PREFIX) && line.ends_with(ALLOCATION_FAILURE_SUFFIX)
+        })
+}
+
+pub fn limit_note(limit_mb: u64, peak_rss_kb: u64) -> String {
+    if peak_rss_kb > limit_mb.saturating_mul(1024) {
+        format!("memory limit of {} MB exceeded (peak {})", limit_mb, format_kb(peak_rss_kb))
+    } else {
+        format!("memory limit of {} MB exceeded: an allocation failed (peak before it {})", limit_mb, format_kb(peak_rss_kb))
+    }
+}
+
+pub fn format_kb(kb: u64) -> String {
+    format!("{:.1} MB", kb as f64 / 1024.0)
+}
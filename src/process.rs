@@ -0,0 +1,216 @@
+use std::io::{self, Read};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Instant;
+
+// 带截止时间运行命令的结果；超时时携带被杀掉之前已产生的输出
+pub enum CommandOutcome {
+    Completed(Output),
+    TimedOut(Output),
+}
+
+// 已结束子进程的资源占用
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Usage {
+    // 子进程的峰值常驻内存（KB）：以 measure_memory 启动时为 exec 之后的程序本身的峰值，
+    // 否则为 wait4 的 ru_maxrss，其中包括 exec 之前（评测器 fork 出的副本）的内存
+    pub peak_rss_kb: u64,
+}
+
+// 运行命令并收集输出；超过 `deadline` 时杀掉整个进程组（cargo 会派生 rustc 和测试进程）
+pub fn run_with_deadline(command: &mut Command, deadline: Instant) -> io::Result<(CommandOutcome, Usage)> {
+    run_with_stdin(command, Stdio::null(), deadline)
+}
+
+// 同 run_with_deadline，子进程的标准输入取自 `stdin`（如 io_judge 用例的 .in 文件）
+pub fn run_with_stdin(command: &mut Command, stdin: Stdio, deadline: Instant) -> io::Result<(CommandOutcome, Usage)> {
+    command.stdin(stdin).stdout(Stdio::piped()).stderr(Stdio::piped()).process_group(0);
+
+    thread::scope(|s| {
+        // 子进程由等待它的线程启动并回收：ptrace 请求只能由创建被跟踪进程的线程发出。主线程按截止时间等待其通知，
+        // 进程结束的时刻不受轮询间隔影响（便于计时）；用 wait4 回收子进程以同时取得其资源占用
+        let (spawned_tx, spawned_rx) = mpsc::channel();
+        let (tx, rx) = mpsc::channel();
+        s.spawn(move || {
+            let mut child = match command.spawn() {
+                Ok(child) => child,
+                Err(e) => {
+                    let _ = spawned_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = spawned_tx.send(Ok((child.id(), child.stdout.take(), child.stderr.take())));
+            let _ = tx.send(wait_with_usage(child.id()));
+        });
+        let (pid, stdout, stderr) = spawned_rx
+            .recv()
+            .unwrap_or_else(|_| Err(io::Error::other("the thread starting the child process exited")))?;
+
+        // 在独立线程中读取管道，防止子进程因缓冲区写满而阻塞
+        let stdout = spawn_reader(stdout);
+        let stderr = spawn_reader(stderr);
+
+        let (status, timed_out) = match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(status) => (Some(status), false),
+            Err(RecvTimeoutError::Timeout) => {
+                kill_process_group(pid);
+                (rx.recv().ok(), true)
+            }
+            Err(RecvTimeoutError::Disconnected) => (None, false),
+        };
+        let (status, usage) =
+            status.unwrap_or_else(|| Err(io::Error::other("the thread waiting for the child process exited")))?;
+
+        let output = Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        };
+
+        let outcome = if timed_out {
+            CommandOutcome::TimedOut(output)
+        } else {
+            CommandOutcome::Completed(output)
+        };
+        Ok((outcome, usage))
+    })
+}
+
+// 以 ptrace 跟踪命令启动的进程，在其退出前（PTRACE_EVENT_EXIT）读取 /proc/<pid>/status 中的 VmHWM，
+// 即 exec 之后程序本身的峰值内存。ru_maxrss 会保留 exec 之前的最大值，对评测器 fork 出的子进程而言就是
+// 评测器自身的内存，小程序的峰值会被它掩盖。无法跟踪时（如被 seccomp 禁止）退回 ru_maxrss
+pub fn measure_memory(command: &mut Command) {
+    unsafe {
+        command.pre_exec(|| {
+            libc::ptrace(libc::PTRACE_TRACEME, 0, std::ptr::null_mut::<libc::c_void>(), std::ptr::null_mut::<libc::c_void>());
+            Ok(())
+        });
+    }
+}
+
+// 回收子进程并取得其退出状态与资源占用；Linux 上 ru_maxrss 以 KB 为单位。被跟踪的子进程每次停止时都会在这里
+// 收到通知：exec 后的 SIGTRAP 用来设置跟踪选项，退出前的停止用来读取峰值内存，其他信号原样转交
+fn wait_with_usage(pid: u32) -> io::Result<(ExitStatus, Usage)> {
+    let pid = pid as libc::pid_t;
+    let mut status = 0;
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    let mut traced = false;
+    let mut peak_rss_kb = None;
+    loop {
+        let result = unsafe { libc::wait4(pid, &mut status, 0, &mut rusage) };
+        if result < 0 {
+            let error = io::Error::last_os_error();
+            if error.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(error);
+        }
+        if !libc::WIFSTOPPED(status) {
+            break;
+        }
+        let signal = libc::WSTOPSIG(status);
+        let event = status >> 16;
+        let inject = if event == libc::PTRACE_EVENT_EXIT {
+            peak_rss_kb = vm_hwm_kb(pid);
+            0
+        } else if event != 0 {
+            // 程序再次 exec（如经由脚本启动）时的停止
+            0
+        } else if !traced && signal == libc::SIGTRAP {
+            traced = true;
+            let options = libc::PTRACE_O_TRACEEXIT | libc::PTRACE_O_TRACEEXEC | libc::PTRACE_O_EXITKILL;
+            unsafe { libc::ptrace(libc::PTRACE_SETOPTIONS, pid, 0, options) };
+            0
+        } else {
+            signal
+        };
+        unsafe { libc::ptrace(libc::PTRACE_CONT, pid, 0, inject) };
+    }
+    let usage = Usage { peak_rss_kb: peak_rss_kb.unwrap_or(rusage.ru_maxrss.max(0) as u64) };
+    Ok((ExitStatus::from_raw(status), usage))
+}
+
+// 进程当前的峰值常驻内存（KB）
+fn vm_hwm_kb(pid: libc::pid_t) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    line.trim().trim_end_matches("kB").trim().parse().ok()
+}
+
+// 限制命令启动的进程可用的内存：以 RLIMIT_DATA 限制堆等私有可写内存，超出时分配失败，
+// Rust 程序会以 "memory allocation of N bytes failed" 中止。不用 RLIMIT_AS，
+// 因为 glibc 为每个线程预留的大块虚拟地址空间并不实际占用内存，却会计入地址空间
+pub fn limit_memory(command: &mut Command, limit_mb: u64) {
+    let bytes = limit_mb.saturating_mul(1024 * 1024) as libc::rlim_t;
+    unsafe {
+        command.pre_exec(move || {
+            let limit = libc::rlimit { rlim_cur: bytes, rlim_max: bytes };
+            if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+fn spawn_reader<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+// 子进程以自身 pid 为进程组号启动，向负 pid 发送 SIGKILL 即可结束整棵进程树
+fn kill_process_group(pid: u32) {
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn run_measured(command: &mut Command, timeout: Duration) -> (CommandOutcome, Usage) {
+        measure_memory(command);
+        run_with_deadline(command, Instant::now() + timeout).unwrap()
+    }
+
+    #[test]
+    fn reports_the_peak_memory_of_the_program_not_of_the_forked_grader() {
+        // 测试进程自身先占用 64 MB：按 ru_maxrss 统计时，fork 出的子进程在 exec 之前就带着这部分内存
+        let ballast = std::hint::black_box(vec![1u8; 64 * 1024 * 1024]);
+        let (outcome, usage) = run_measured(&mut Command::new("true"), Duration::from_secs(10));
+        assert!(matches!(outcome, CommandOutcome::Completed(ref output) if output.status.success()));
+        assert!(usage.peak_rss_kb > 0 && usage.peak_rss_kb < 8 * 1024, "peak {} KB", usage.peak_rss_kb);
+        drop(ballast);
+    }
+
+    #[test]
+    fn keeps_the_exit_status_and_output_of_a_measured_program() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo out; kill -USR1 $$"]);
+        let (outcome, _) = run_measured(&mut command, Duration::from_secs(10));
+        let CommandOutcome::Completed(output) = outcome else {
+            panic!("the program timed out");
+        };
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.status.signal(), Some(libc::SIGUSR1));
+    }
+
+    #[test]
+    fn kills_a_measured_program_at_the_deadline() {
+        let mut command = Command::new("sleep");
+        command.arg("10");
+        let started = Instant::now();
+        let (outcome, _) = run_measured(&mut command, Duration::from_millis(200));
+        assert!(matches!(outcome, CommandOutcome::TimedOut(_)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,243 @@
+use super::cache::collect_files;
+use super::judge::CASES_DIR;
+use super::{Exercise, EXERCISES_DIR};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// reset 前备份学生当前代码的目录
+pub const BACKUP_DIR: &str = "backups";
+
+// 各习题发给学生时的初始代码，目录结构与 exercises/ 相同；reset 默认从这里恢复
+pub const STARTING_CODE_DIR: &str = "starting_code";
+
+// 单文件习题中测试模块的属性行，其后紧跟 `mod 名称 {`
+const TEST_SECTION_START: &str = "#[cfg(test)]";
+
+// 将单文件习题拆分为 (代码, 测试) 两部分；测试部分从最后一个顶层的 `#[cfg(test)]` 测试模块开始，
+// 代码中带 `#[cfg(test)]` 的辅助函数或 use 不影响拆分
+pub fn split_tests(source: &str) -> (&str, &str) {
+    let mut split = None;
+    // 尚未确定修饰的是否为模块的 `#[cfg(test)]` 所在的位置
+    let mut attribute = None;
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let line = line.trim_end();
+        if line == TEST_SECTION_START {
+            attribute = Some(line_start);
+            continue;
+        }
+        // 属性与模块之间可以有空行或其他属性
+        if line.is_empty() || line.starts_with("#[") {
+            continue;
+        }
+        if let Some(start) = attribute.take() {
+            if line.starts_with("mod ") && line.ends_with('{') {
+                split = Some(start);
+            }
+        }
+    }
+    match split {
+        Some(split) => source.split_at(split),
+        None => (source, ""),
+    }
+}
+
+// cargo 项目中的测试文件：src/tests.rs、tests/ 目录、io_judge 习题的 cases/ 目录以及 Cargo.toml 中 `[[test]]` 指定的路径
+pub fn is_test_file(project: &Path, relative: &Path) -> bool {
+    if relative == Path::new("src/tests.rs") || relative.starts_with("tests") || relative.starts_with(CASES_DIR) {
+        return true;
+    }
+    test_target_paths(project).iter().any(|path| path == relative)
+}
+
+// 简单解析 Cargo.toml 中各 `[[test]]` 段的 `path`
+fn test_target_paths(project: &Path) -> Vec<PathBuf> {
+    let Ok(manifest) = fs::read_to_string(project.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let mut paths = Vec::new();
+    let mut in_test = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_test = line == "[[test]]";
+        } else if let Some(value) = line.strip_prefix("path").map(str::trim_start) {
+            if let (true, Some(value)) = (in_test, value.strip_prefix('=')) {
+                paths.push(PathBuf::from(value.trim().trim_matches('"')));
+            }
+        }
+    }
+    paths
+}
+
+pub fn git(args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(output.stdout)
+}
+
+// 习题初始代码中的各文件：(习题中对应的路径, 初始内容)。指定 `revision` 时取该 git 版本中的习题，
+// 否则取 starting_code/ 中随仓库提供的初始代码
+fn starting_files(exercise: &Exercise, revision: Option<&str>) -> Result<Vec<(PathBuf, Vec<u8>)>, String> {
+    let exercise_path = Path::new(EXERCISES_DIR).join(&exercise.path);
+    let Some(revision) = revision else {
+        let starting = Path::new(STARTING_CODE_DIR).join(&exercise.path);
+        let mut files = Vec::new();
+        if starting.is_dir() {
+            collect_files(&starting, &mut files).map_err(|e| format!("Failed to read {}: {}", starting.display(), e))?;
+        } else if starting.is_file() {
+            let content = fs::read(&starting).map_err(|e| format!("Failed to read {}: {}", starting.display(), e))?;
+            return Ok(vec![(exercise_path, content)]);
+        } else {
+            return Err(format!(
+                "No starting code for {} in {} (use --from <revision> to reset from git)",
+                exercise.name,
+                starting.display()
+            ));
+        }
+        return files
+            .into_iter()
+            .map(|file| {
+                let target = exercise_path.join(file.strip_prefix(&starting).unwrap_or(Path::new("")));
+                let content = fs::read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+                Ok((target, content))
+            })
+            .collect();
+    };
+
+    let git_path = format!("{}/{}", EXERCISES_DIR, exercise.path);
+    let listing = git(&["ls-tree", "-r", "--name-only", revision, "--", &git_path])?;
+    let files: Vec<String> = String::from_utf8_lossy(&listing).lines().map(str::to_string).collect();
+    if files.is_empty() {
+        return Err(format!("{} does not exist in revision {}", git_path, revision));
+    }
+    files
+        .into_iter()
+        .map(|file| Ok((PathBuf::from(&file), git(&["show", &format!("{}:{}", revision, file)])?)))
+        .collect()
+}
+
+// 恢复习题的初始代码（默认取 starting_code/，指定 `revision` 时取该 git 版本），测试文件（单文件习题为
+// `#[cfg(test)]` 之后的部分）保持不变；恢复前把当前代码完整备份到 backups/<习题名>/<时间戳>/，返回备份目录。
+// 代码已与初始代码相同时不做任何改动
+pub fn reset_exercise(exercise: &Exercise, revision: Option<&str>) -> Result<PathBuf, String> {
+    let project = Path::new(EXERCISES_DIR).join(&exercise.path);
+    let mut changes = Vec::new();
+    for (target, original) in starting_files(exercise, revision)? {
+        let current = fs::read(&target).ok();
+        let restored = if exercise.exercise_type == "single_file" {
+            let original = String::from_utf8_lossy(&original).into_owned();
+            let current = current.as_deref().map(String::from_utf8_lossy).unwrap_or_default();
+            let (code, original_tests) = split_tests(&original);
+            let (_, current_tests) = split_tests(&current);
+            // 学生删掉了测试部分时恢复原来的测试
+            let tests = if current_tests.is_empty() { original_tests } else { current_tests };
+            format!("{}{}", code, tests).into_bytes()
+        } else {
+            let relative = target.strip_prefix(&project).unwrap_or(&target);
+            if relative == Path::new("Cargo.toml") || is_test_file(&project, relative) {
+                continue;
+            }
+            original
+        };
+        if current.as_ref() != Some(&restored) {
+            changes.push((target, restored));
+        }
+    }
+    if changes.is_empty() {
+        return Err(format!("{} already matches its starting code; nothing to reset", exercise.name));
+    }
+
+    let backup = backup_exercise(exercise).map_err(|e| format!("Failed to back up {}: {}", exercise.name, e))?;
+    for (target, restored) in changes {
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        fs::write(&target, restored).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    }
+    Ok(backup)
+}
+
+fn backup_exercise(exercise: &Exercise) -> io::Result<PathBuf> {
+    let backup = create_backup_dir(&exercise.name)?;
+    let source = exercise.full_path();
+    if source.is_dir() {
+        copy_dir(&source, &backup)?;
+    } else {
+        fs::create_dir_all(&backup)?;
+        if let Some(file_name) = source.file_name() {
+            fs::copy(&source, backup.join(file_name))?;
+        }
+    }
+    Ok(backup)
+}
+
+// 新建一次备份的目录 backups/<习题名>/<毫秒时间戳>/；目录已存在（同一毫秒内的多次 reset）时依次加上 -1、-2 等后缀，
+// 已有的备份永远不会被覆盖
+fn create_backup_dir(name: &str) -> io::Result<PathBuf> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+    let parent = Path::new(BACKUP_DIR).join(name);
+    fs::create_dir_all(&parent)?;
+    let mut attempt = 0;
+    loop {
+        let dir = match attempt {
+            0 => parent.join(timestamp.to_string()),
+            _ => parent.join(format!("{}-{}", timestamp, attempt)),
+        };
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// 递归复制目录，跳过构建产物
+pub fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_name() == "target" {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_the_test_module() {
+        let source = "fn f() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}\n";
+        assert_eq!(split_tests(source), ("fn f() {}\n\n", "#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}\n"));
+    }
+
+    #[test]
+    fn ignores_test_only_helpers_above_the_test_module() {
+        let code = "#[cfg(test)]\nuse std::fmt;\n\n#[cfg(test)]\nfn helper() -> i32 {\n    1\n}\n\n";
+        let tests = "#[cfg(test)]\n#[allow(unused)]\nmod test_graph {\n}\n";
+        assert_eq!(split_tests(&format!("{}{}", code, tests)), (code, tests));
+    }
+
+    #[test]
+    fn keeps_everything_as_code_without_a_test_module() {
+        let source = "fn f() {}\n#[cfg(test)]\nfn helper() {}\n";
+        assert_eq!(split_tests(source), (source, ""));
+    }
+}
@@ -0,0 +1,175 @@
+use super::reset::STARTING_CODE_DIR;
+use super::validate::{self, Severity};
+use super::EXERCISES_DIR;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 新习题的模板，编译进评测器，`{{name}}` 替换为习题名
+const SINGLE_FILE_TEMPLATE: &str = include_str!("../templates/single_file.rs");
+const CARGO_TEMPLATE: [(&str, &str); 4] = [
+    ("Cargo.toml", include_str!("../templates/cargo_project/Cargo.toml.template")),
+    ("src/main.rs", include_str!("../templates/cargo_project/src/main.rs")),
+    ("src/tests.rs", include_str!("../templates/cargo_project/src/tests.rs")),
+    // 习题模块，文件名与习题同名
+    ("src/module.rs", include_str!("../templates/cargo_project/src/module.rs")),
+];
+const IO_JUDGE_TEMPLATE: [(&str, &str); 4] = [
+    ("Cargo.toml", include_str!("../templates/io_judge/Cargo.toml.template")),
+    ("src/main.rs", include_str!("../templates/io_judge/src/main.rs")),
+    ("cases/1.in", include_str!("../templates/io_judge/cases/1.in")),
+    ("cases/1.out", include_str!("../templates/io_judge/cases/1.out")),
+];
+
+pub struct NewExercise<'a> {
+    pub name: &'a str,
+    pub exercise_type: &'a str,
+    pub level: &'a str,
+    pub score: Option<i32>,
+}
+
+// 习题名同时用作 crate 名、模块名与测试函数名，只允许小写字母、数字与下划线
+fn check_name(name: &str) -> Result<(), String> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid exercise name: {} (use lowercase letters, digits and underscores, starting with a letter)",
+            name
+        ))
+    }
+}
+
+fn render(template: &str, name: &str) -> String {
+    template.replace("{{name}}", name)
+}
+
+// 按模板生成习题并登记到配置文件中，返回生成的习题路径；习题名或路径已存在时拒绝覆盖
+pub fn create_exercise(config_path: &Path, new: &NewExercise) -> Result<PathBuf, String> {
+    let name = new.name.strip_suffix(".rs").unwrap_or(new.name);
+    check_name(name)?;
+
+    let content = fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read {}: {}", config_path.display(), e))?;
+    let mut config: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let tiers = config
+        .as_object_mut()
+        .ok_or_else(|| format!("{} is not an object of difficulty tiers", config_path.display()))?;
+
+    let (config_name, path) = match new.exercise_type {
+        "single_file" => (format!("{}.rs", name), format!("{}/{}.rs", new.level, name)),
+        // 模块文件与 main.rs、tests.rs 同在 src/ 下，不能与之重名
+        "cargo_project" if matches!(name, "main" | "tests") => {
+            return Err(format!("Invalid exercise name: {} (clashes with src/{}.rs)", name, name));
+        }
+        "cargo_project" | "io_judge" => (name.to_string(), format!("{}/{}", new.level, name)),
+        other => {
+            return Err(format!(
+                "Unknown exercise type: {} (expected single_file, cargo_project or io_judge)",
+                other
+            ));
+        }
+    };
+
+    let existing = tiers.values().filter_map(exercise_list).flatten().any(|exercise| {
+        exercise.get("name").and_then(Value::as_str) == Some(config_name.as_str())
+            || exercise.get("path").and_then(Value::as_str) == Some(path.as_str())
+    });
+    if existing {
+        return Err(format!("Exercise {} is already registered in {}", config_name, config_path.display()));
+    }
+    let full_path = Path::new(EXERCISES_DIR).join(&path);
+    if full_path.exists() {
+        return Err(format!("{} already exists, refusing to overwrite it", full_path.display()));
+    }
+
+    if let Some(tier) = tiers.get(new.level) {
+        if exercise_list(tier).is_none() {
+            return Err(format!("Tier {} in {} has no exercise list", new.level, config_path.display()));
+        }
+    }
+    let default_score = tiers.get(new.level).and_then(|tier| tier.get("default_score")).and_then(Value::as_i64);
+    if new.score.is_none() && default_score.is_none() {
+        return Err(format!("Missing --score (tier {} has no default_score)", new.level));
+    }
+
+    let mut exercise = Map::new();
+    exercise.insert("name".to_string(), Value::from(config_name.as_str()));
+    exercise.insert("path".to_string(), Value::from(path.as_str()));
+    exercise.insert("type".to_string(), Value::from(new.exercise_type));
+    if let Some(score) = new.score {
+        exercise.insert("score".to_string(), Value::from(score));
+    }
+
+    // 新难度追加在末尾，沿用数组写法
+    let tier = tiers.entry(new.level).or_insert_with(|| Value::Array(Vec::new()));
+    let exercises = match tier {
+        Value::Object(fields) => fields.get_mut("exercises"),
+        other => Some(other),
+    };
+    if let Some(Value::Array(exercises)) = exercises {
+        exercises.push(Value::Object(exercise));
+    }
+
+    // 先检查登记后的配置（如难度总分是否仍与约定一致），有错误时不生成任何文件
+    let problems = validate::validate_value(&config, Path::new(EXERCISES_DIR), &[path.as_str()]);
+    if validate::has_errors(&problems) {
+        let errors: Vec<String> = problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Error)
+            .map(|problem| format!("  {}", problem))
+            .collect();
+        return Err(format!(
+            "Not creating {}: {} would become invalid:\n{}",
+            config_name,
+            config_path.display(),
+            errors.join("\n")
+        ));
+    }
+
+    write_files(&full_path, new.exercise_type, name)
+        .map_err(|e| format!("Failed to create {}: {}", full_path.display(), e))?;
+    // 模板本身就是习题的初始代码，供 reset 恢复
+    let starting_path = Path::new(STARTING_CODE_DIR).join(&path);
+    write_files(&starting_path, new.exercise_type, name)
+        .map_err(|e| format!("Failed to create {}: {}", starting_path.display(), e))?;
+
+    let mut content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    content.push('\n');
+    fs::write(config_path, content).map_err(|e| format!("Failed to write {}: {}", config_path.display(), e))?;
+
+    Ok(full_path)
+}
+
+// 难度中的习题列表，兼容数组与带元数据的对象两种写法
+fn exercise_list(tier: &Value) -> Option<&Vec<Value>> {
+    match tier {
+        Value::Object(fields) => fields.get("exercises").and_then(Value::as_array),
+        other => other.as_array(),
+    }
+}
+
+fn write_files(full_path: &Path, exercise_type: &str, name: &str) -> std::io::Result<()> {
+    if exercise_type == "single_file" {
+        if let Some(dir) = full_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        return fs::write(full_path, render(SINGLE_FILE_TEMPLATE, name));
+    }
+
+    let template = if exercise_type == "io_judge" { IO_JUDGE_TEMPLATE } else { CARGO_TEMPLATE };
+    for (file, template) in template {
+        let target = match file {
+            "src/module.rs" => full_path.join(format!("src/{}.rs", name)),
+            _ => full_path.join(file),
+        };
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(target, render(template, name))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+// 习题的计分方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringMode {
+    // 全部通过才得分（默认）
+    #[default]
+    AllOrNothing,
+    // 按测试自身打印的 `Total score: NN.NN` 或通过的 #[test] 数量给部分分
+    Partial,
+}
+
+impl ScoringMode {
+    pub fn is_all_or_nothing(&self) -> bool {
+        *self == ScoringMode::AllOrNothing
+    }
+}
+
+// 从测试输出推算得分比例（0.0 ~ 1.0）：优先使用测试打印的百分制总分，其次使用 libtest 的通过数
+pub fn partial_fraction(test_stdout: &str) -> Option<f64> {
+    parse_total_score(test_stdout).or_else(|| {
+        let (passed, failed) = parse_libtest_counts(test_stdout)?;
+        let total = passed + failed;
+        (total > 0).then(|| passed as f64 / total as f64)
+    })
+}
+
+// 解析 `Total score: NN.NN`（百分制）。测试在调用学生代码之后才打印总分，学生代码自己打印的同样格式的行
+// 都在它之前，因此只取最后一行
+fn parse_total_score(stdout: &str) -> Option<f64> {
+    let score = stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Total score:"))
+        .next_back()?
+        .trim()
+        .parse::<f64>()
+        .ok()?;
+    Some((score / 100.0).clamp(0.0, 1.0))
+}
+
+// 汇总所有 `test result: ... N passed; M failed; ...` 行
+pub fn parse_libtest_counts(stdout: &str) -> Option<(usize, usize)> {
+    let mut found = false;
+    let (mut passed, mut failed) = (0, 0);
+    for line in stdout.lines().filter(|line| line.starts_with("test result:")) {
+        found = true;
+        for part in line.split(';') {
+            let mut words = part.split_whitespace().rev();
+            let (Some(label), Some(count)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Ok(count) = count.parse::<usize>() else {
+                continue;
+            };
+            match label {
+                "passed" => passed += count,
+                "failed" => failed += count,
+                _ => {}
+            }
+        }
+    }
+    found.then_some((passed, failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_sum_over_all_test_result_lines() {
+        let stdout = "\
+running 3 tests
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+running 2 tests
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 1 filtered out; finished in 0.01s
+";
+        assert_eq!(parse_libtest_counts(stdout), Some((4, 1)));
+    }
+
+    #[test]
+    fn counts_ignore_lines_that_only_mention_test_result() {
+        assert_eq!(parse_libtest_counts("running 0 tests\n  test result: ok. 1 passed;\n"), None);
+        assert_eq!(parse_libtest_counts("test result: ok. 0 passed; 0 failed;"), Some((0, 0)));
+    }
+
+    #[test]
+    fn fraction_prefers_the_printed_total_score() {
+        let stdout = "Total score: 50.00\ntest result: FAILED. 1 passed; 3 failed;\n";
+        assert_eq!(partial_fraction(stdout), Some(0.5));
+    }
+
+    #[test]
+    fn fraction_ignores_total_scores_printed_by_the_student_code() {
+        let stdout = "\
+running 1 test
+test tests::test_count ... FAILED
+
+failures:
+
+---- tests::test_count stdout ----
+Total score: 100.00
+Total score: 100.00
+Total score: 100.00
+Total score: 40.00
+
+thread 'tests::test_count' panicked at src/tests.rs:26:9:
+assertion `left == right` failed
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+        assert_eq!(partial_fraction(stdout), Some(0.4));
+    }
+
+    #[test]
+    fn fraction_clamps_the_printed_total_score() {
+        assert_eq!(partial_fraction("Total score: 120.5"), Some(1.0));
+    }
+
+    #[test]
+    fn fraction_falls_back_to_passed_tests() {
+        assert_eq!(partial_fraction("test result: FAILED. 1 passed; 3 failed;"), Some(0.25));
+        assert_eq!(partial_fraction("test result: ok. 0 passed; 0 failed;"), None);
+        assert_eq!(partial_fraction("error: could not compile"), None);
+    }
+}
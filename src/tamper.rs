@@ -0,0 +1,226 @@
+use super::reset::{git, is_test_file, split_tests};
+use super::{Exercise, ExerciseConfig, EXERCISES_DIR};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 官方测试的指纹清单，随仓库一起提交
+pub const TEST_MANIFEST: &str = "test_manifest.json";
+
+const CARGO_MANIFEST: &str = "Cargo.toml";
+
+// 每道习题的测试文件（相对仓库根目录的路径）及其官方测试。清单自身保存官方测试的内容，
+// 恢复时不依赖生成清单时的 git 提交（合并时被 squash 或 rebase 掉的提交不再存在）
+#[derive(Serialize, Deserialize, Default)]
+pub struct TestManifest {
+    pub exercises: BTreeMap<String, BTreeMap<String, OfficialTests>>,
+}
+
+// 单文件习题为测试模块（`#[cfg(test)] mod ... {` 起的部分），cargo 项目为整个测试文件
+#[derive(Serialize, Deserialize)]
+pub struct OfficialTests {
+    pub sha256: String,
+    pub source: String,
+}
+
+impl TestManifest {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_slice(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        content.push('\n');
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    // 与清单不一致（被修改或删除）的测试文件，以及 cargo 项目中清单没有记录的测试文件（如 Cargo.toml 中
+    // 新增的 `[[test]]` 目标）；清单中没有记录的习题不检查
+    pub fn modified_files(&self, exercise: &Exercise) -> Vec<String> {
+        let Some(files) = self.exercises.get(&exercise.name) else {
+            return Vec::new();
+        };
+        let mut modified: Vec<String> = files
+            .iter()
+            .filter(|(file, official)| {
+                fs::read(file).ok().map(|content| fingerprint(exercise, &content)).as_ref() != Some(&official.sha256)
+            })
+            .map(|(file, _)| file.clone())
+            .collect();
+        let project = Path::new(EXERCISES_DIR).join(&exercise.path);
+        if project.is_dir() {
+            let mut unofficial = Vec::new();
+            collect_test_files(&project, &project, &mut unofficial);
+            modified.extend(unofficial.into_iter().filter(|file| !files.contains_key(file)));
+        }
+        modified
+    }
+
+    // 按清单中保存的内容恢复官方测试；单文件习题保留当前的代码部分，只替换测试部分
+    pub fn restore(&self, exercise: &Exercise, files: &[String]) -> Result<(), String> {
+        let Some(official) = self.exercises.get(&exercise.name) else {
+            return Ok(());
+        };
+        for file in files {
+            // 清单中没有的测试文件无从恢复，恢复 Cargo.toml 后通常也不再是测试目标
+            let Some(tests) = official.get(file) else {
+                continue;
+            };
+            let restored = if exercise.exercise_type == "single_file" {
+                let current = fs::read_to_string(file).unwrap_or_default();
+                format!("{}{}", split_tests(&current).0, tests.source)
+            } else {
+                tests.source.clone()
+            };
+            if let Some(dir) = Path::new(file).parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            fs::write(file, restored).map_err(|e| format!("Failed to write {}: {}", file, e))?;
+        }
+        Ok(())
+    }
+}
+
+// 文件中的测试部分
+fn test_source(exercise: &Exercise, content: &[u8]) -> String {
+    let source = String::from_utf8_lossy(content);
+    if exercise.exercise_type == "single_file" {
+        split_tests(&source).1.to_string()
+    } else {
+        source.into_owned()
+    }
+}
+
+fn fingerprint(exercise: &Exercise, content: &[u8]) -> String {
+    sha256(&test_source(exercise, content))
+}
+
+fn sha256(source: &str) -> String {
+    Sha256::digest(source.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 项目目录中当前的测试文件（按其中的 Cargo.toml 判断），跳过构建产物
+fn collect_test_files(project: &Path, dir: &Path, files: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if entry.file_name() != "target" {
+                collect_test_files(project, &path, files);
+            }
+        } else if path.strip_prefix(project).is_ok_and(|relative| is_test_file(project, relative)) {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+// 习题在指定 git 版本中的测试文件；cargo 项目还包括 Cargo.toml，其中的 `[[test]]`、`[lib]` 等目标决定运行哪些测试
+fn test_files(exercise: &Exercise, revision: &str) -> Result<Vec<String>, String> {
+    let git_path = format!("{}/{}", EXERCISES_DIR, exercise.path);
+    let listing = git(&["ls-tree", "-r", "--name-only", revision, "--", &git_path])?;
+    let files = String::from_utf8_lossy(&listing).lines().map(str::to_string).collect::<Vec<_>>();
+    if exercise.exercise_type == "single_file" {
+        return Ok(files);
+    }
+    let project = PathBuf::from(&git_path);
+    Ok(files
+        .into_iter()
+        .filter(|file| {
+            Path::new(file).strip_prefix(&project).is_ok_and(|relative| {
+                relative == Path::new(CARGO_MANIFEST) || is_test_file(&project, relative)
+            })
+        })
+        .collect())
+}
+
+// 按指定 git 版本（默认 HEAD）中的官方测试生成清单
+pub fn build_manifest(config: &ExerciseConfig, revision: Option<&str>) -> Result<TestManifest, String> {
+    let revision = git(&["rev-parse", "--verify", &format!("{}^{{commit}}", revision.unwrap_or("HEAD"))])?;
+    let revision = String::from_utf8_lossy(&revision).trim().to_string();
+    let mut manifest = TestManifest::default();
+
+    for (_, exercise) in config.exercises() {
+        let mut official = BTreeMap::new();
+        for file in test_files(exercise, &revision)? {
+            let content = git(&["show", &format!("{}:{}", revision, file)])?;
+            let source = test_source(exercise, &content);
+            official.insert(file, OfficialTests { sha256: sha256(&source), source });
+        }
+        if !official.is_empty() {
+            manifest.exercises.insert(exercise.name.clone(), official);
+        }
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = "[package]\nname = \"p\"\n\n[[test]]\nname = \"tests\"\npath = \"src/tests.rs\"\n";
+    const TESTS: &str = "#[test]\nfn official() {\n    assert_eq!(p::count(), 3);\n}\n";
+
+    // 在临时目录中建立 cargo 项目及其清单；习题路径为绝对路径，清单中的文件按同样的方式拼接
+    fn project(name: &str) -> (PathBuf, Exercise, TestManifest) {
+        let dir = std::env::temp_dir().join(format!("cargotest-tamper-{}-{}", std::process::id(), name));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join(CARGO_MANIFEST), CARGO_TOML).unwrap();
+        fs::write(dir.join("src/tests.rs"), TESTS).unwrap();
+        fs::write(dir.join("src/lib.rs"), "pub fn count() -> i32 {\n    3\n}\n").unwrap();
+        let exercise: Exercise = serde_json::from_value(serde_json::json!({
+            "name": "p", "path": dir.to_string_lossy(), "type": "cargo_project"
+        }))
+        .unwrap();
+        let official = [(CARGO_MANIFEST, CARGO_TOML), ("src/tests.rs", TESTS)]
+            .into_iter()
+            .map(|(file, source)| {
+                let tests = OfficialTests { sha256: sha256(source), source: source.to_string() };
+                (dir.join(file).to_string_lossy().into_owned(), tests)
+            })
+            .collect();
+        let manifest = TestManifest { exercises: BTreeMap::from([("p".to_string(), official)]) };
+        (dir, exercise, manifest)
+    }
+
+    #[test]
+    fn accepts_the_official_tests() {
+        let (dir, exercise, manifest) = project("official");
+        let modified = manifest.modified_files(&exercise);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(modified.is_empty(), "{:?}", modified);
+    }
+
+    #[test]
+    fn rejects_a_test_target_pointed_at_another_file() {
+        let (dir, exercise, manifest) = project("retarget");
+        fs::write(dir.join(CARGO_MANIFEST), CARGO_TOML.replace("src/tests.rs", "src/fake.rs")).unwrap();
+        fs::write(dir.join("src/fake.rs"), "#[test]\nfn fake() {}\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), "pub fn count() -> i32 {\n    999\n}\n").unwrap();
+        let modified = manifest.modified_files(&exercise);
+        manifest.restore(&exercise, &modified).unwrap();
+        let after_restore = manifest.modified_files(&exercise);
+        let cargo_toml = fs::read_to_string(dir.join(CARGO_MANIFEST)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let relative: Vec<&str> =
+            modified.iter().map(|file| Path::new(file).strip_prefix(&dir).unwrap().to_str().unwrap()).collect();
+        assert_eq!(relative, [CARGO_MANIFEST, "src/fake.rs"]);
+        assert!(after_restore.is_empty(), "{:?}", after_restore);
+        assert_eq!(cargo_toml, CARGO_TOML);
+    }
+
+    #[test]
+    fn rejects_test_files_added_to_the_tests_directory() {
+        let (dir, exercise, manifest) = project("added");
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        fs::write(dir.join("tests/extra.rs"), "#[test]\nfn extra() {}\n").unwrap();
+        let modified = manifest.modified_files(&exercise);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(modified, [dir.join("tests/extra.rs").to_string_lossy()]);
+    }
+}
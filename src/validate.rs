@@ -0,0 +1,499 @@
+use super::compile::{OptLevel, EDITIONS};
+use super::differential::DifferentialSpec;
+use super::judge::CASES_DIR;
+use super::lint::ClippyPolicy;
+use super::{BenchmarkSpec, DetailedTier, Exercise, FormatPolicy, DEFAULT_TIMEOUT_SECS};
+use colored::Color;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// README 中约定的各难度总分；配置中的 expected_total 优先
+const EXPECTED_TIER_TOTALS: [(&str, i64); 3] = [("easy", 20), ("normal", 30), ("hard", 50)];
+
+const EXERCISE_TYPES: [&str; 3] = ["single_file", "cargo_project", "io_judge"];
+
+// 只对单文件习题生效的编译选项
+const RUSTC_FIELDS: [&str; 4] = ["edition", "opt_level", "rustc_flags", "warnings"];
+
+// io_judge 习题只按用例的输入输出评测，不运行注入的测试，也不做性能评测与 clippy 检查
+const IO_JUDGE_UNSUPPORTED: [&str; 4] = ["hidden_tests", "differential", "benchmark", "clippy"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// 配置中的一个问题；location 为 JSON Pointer（如 `/hard/2/path`），与磁盘相关的警告为文件路径
+#[derive(Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", severity, self.location, self.message)
+    }
+}
+
+pub fn has_errors(problems: &[Problem]) -> bool {
+    problems.iter().any(|p| p.severity == Severity::Error)
+}
+
+struct Validator<'a> {
+    exercises_root: &'a Path,
+    // 即将由 `new` 创建、视为已存在的习题路径
+    created: &'a [&'a str],
+    problems: Vec<Problem>,
+    // 习题名 -> 首次出现的位置，用于报告重名
+    names: HashMap<String, String>,
+    // 配置中引用过的路径，用于发现磁盘上未登记的习题
+    paths: Vec<String>,
+}
+
+impl Validator<'_> {
+    fn error(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem { severity: Severity::Error, location: location.into(), message: message.into() });
+    }
+
+    fn warning(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem { severity: Severity::Warning, location: location.into(), message: message.into() });
+    }
+
+    // 字段的类型、取值与未知字段都由评测器加载配置时所用的类型反序列化检查，这里只检查 serde 无法表达的约束。
+    // 习题逐个反序列化，使错误能定位到具体的习题
+    fn check_tier(&mut self, tier: &str, value: &Value) {
+        let location = format!("/{}", tier);
+        let (exercises, exercises_location, metadata) = match value {
+            Value::Array(exercises) => (exercises, location.clone(), None),
+            Value::Object(fields) => {
+                let Some(Value::Array(exercises)) = fields.get("exercises") else {
+                    self.error(location, "expected an array of exercises in `exercises`");
+                    return;
+                };
+                let mut fields = fields.clone();
+                fields.insert("exercises".to_string(), Value::Array(Vec::new()));
+                let metadata = match DetailedTier::deserialize(&Value::Object(fields)) {
+                    Ok(metadata) => Some(metadata),
+                    Err(e) => {
+                        self.error(location.clone(), e.to_string());
+                        None
+                    }
+                };
+                (exercises, format!("{}/exercises", location), metadata)
+            }
+            _ => {
+                self.error(location, "expected an array of exercises or a tier object");
+                return;
+            }
+        };
+        if let Some(metadata) = &metadata {
+            self.check_tier_metadata(&location, metadata);
+        }
+        let default_score = metadata.as_ref().and_then(|m| m.default_score);
+        let expected_total = metadata.as_ref().and_then(|m| m.expected_total);
+
+        let mut total = Some(0);
+        for (index, value) in exercises.iter().enumerate() {
+            let location = format!("{}/{}", exercises_location, index);
+            let score = match Exercise::deserialize(value) {
+                Ok(exercise) => self.check_exercise(&location, value, &exercise, default_score),
+                Err(e) => {
+                    self.error(location, e.to_string());
+                    // 仍记下路径，避免再把这道习题报告为未登记
+                    self.paths.extend(value.get("path").and_then(Value::as_str).map(str::to_string));
+                    None
+                }
+            };
+            total = total.zip(score).map(|(total, score)| total + score);
+        }
+
+        // 有习题无法解析或分值无效时总分没有意义，不再报告总分不符
+        let Some(total) = total else {
+            return;
+        };
+        // 未显式给出 expected_total 时，README 中约定的难度仍按约定的总分检查
+        let expected = expected_total.map(i64::from).or_else(|| {
+            EXPECTED_TIER_TOTALS.iter().find(|(name, _)| *name == tier).map(|(_, total)| *total)
+        });
+        if let Some(expected) = expected.filter(|expected| total != *expected) {
+            let source = if expected_total.is_some() { "expected_total" } else { "the README" };
+            self.error(
+                location,
+                format!("scores add up to {}, but {} specifies {} for this tier", total, source, expected),
+            );
+        }
+    }
+
+    fn check_tier_metadata(&mut self, location: &str, metadata: &DetailedTier) {
+        if metadata.color.as_deref().is_some_and(|color| color.parse::<Color>().is_err()) {
+            self.error(format!("{}/color", location), "expected a terminal colour name such as `green`");
+        }
+        self.positive(location, "default_score", metadata.default_score.map(i64::from));
+        self.positive(location, "expected_total", metadata.expected_total.map(i64::from));
+        if let Some(FormatPolicy::Deduct(n)) = metadata.format_check {
+            self.positive(&format!("{}/format_check", location), "deduct", Some(n.into()));
+        }
+    }
+
+    fn positive(&mut self, location: &str, field: &str, value: Option<i64>) {
+        if value.is_some_and(|n| n <= 0) {
+            self.error(format!("{}/{}", location, field), "expected a positive integer");
+        }
+    }
+
+    fn non_empty(&mut self, location: &str, field: &str, value: &str) -> bool {
+        let empty = value.trim().is_empty();
+        if empty {
+            self.error(format!("{}/{}", location, field), "expected a non-empty string");
+        }
+        !empty
+    }
+
+    // 检查单道已解析的习题，返回其分值（无效时为 None）；`value` 为习题的原始 JSON，用于判断字段是否出现
+    fn check_exercise(&mut self, location: &str, value: &Value, exercise: &Exercise, default_score: Option<i32>) -> Option<i64> {
+        if self.non_empty(location, "name", &exercise.name) {
+            if let Some(first) = self.names.get(&exercise.name) {
+                let message = format!("duplicate exercise name `{}` (first defined at {})", exercise.name, first);
+                self.error(format!("{}/name", location), message);
+            } else {
+                self.names.insert(exercise.name.clone(), format!("{}/name", location));
+            }
+        }
+
+        let exercise_type = exercise.exercise_type.as_str();
+        let known_type = EXERCISE_TYPES.contains(&exercise_type);
+        if !known_type {
+            self.error(
+                format!("{}/type", location),
+                format!("unknown type `{}` (expected one of: {})", exercise_type, EXERCISE_TYPES.join(", ")),
+            );
+        }
+
+        if self.non_empty(location, "path", &exercise.path) {
+            self.check_path(&format!("{}/path", location), &exercise.path, known_type.then_some(exercise_type));
+            self.paths.push(exercise.path.clone());
+        }
+
+        let score = match exercise.score.or(default_score) {
+            Some(score) if score > 0 => Some(i64::from(score)),
+            Some(_) => {
+                self.error(format!("{}/score", location), "expected a positive integer");
+                None
+            }
+            None => {
+                self.error(location, "missing required field `score` (and the tier has no `default_score`)");
+                None
+            }
+        };
+
+        self.positive(location, "timeout_secs", exercise.timeout_secs.map(|t| t as i64));
+        self.positive(location, "memory_limit_mb", exercise.memory_limit_mb.map(|m| m as i64));
+
+        // 隐藏测试位于仓库之外，只在评测时指定，这里不检查文件是否存在
+        if let Some(hidden) = &exercise.hidden_tests {
+            if self.non_empty(location, "hidden_tests", hidden) && !hidden.ends_with(".rs") {
+                self.error(format!("{}/hidden_tests", location), format!("`{}` is not a .rs file", hidden));
+            }
+        }
+
+        if let Some(differential) = &exercise.differential {
+            self.check_differential(&format!("{}/differential", location), differential);
+        }
+
+        if let Some(benchmark) = &exercise.benchmark {
+            let timeout_secs = exercise.timeout_secs.filter(|t| *t > 0).unwrap_or(DEFAULT_TIMEOUT_SECS);
+            self.check_benchmark(&format!("{}/benchmark", location), benchmark, timeout_secs);
+        }
+
+        if exercise_type == "io_judge" {
+            for field in IO_JUDGE_UNSUPPORTED.iter().filter(|field| value.get(field).is_some()) {
+                self.error(format!("{}/{}", location, field), "not supported for io_judge exercises");
+            }
+        }
+
+        // cargo 项目与 io_judge 习题由 cargo 编译，edition 等在各自的 Cargo.toml 中指定
+        if known_type && exercise_type != "single_file" {
+            for field in RUSTC_FIELDS.iter().filter(|field| value.get(field).is_some()) {
+                self.error(
+                    format!("{}/{}", location, field),
+                    "only supported for single_file exercises (set it in Cargo.toml instead)",
+                );
+            }
+        }
+        if let Some(edition) = exercise.edition.as_deref().filter(|e| !EDITIONS.contains(e)) {
+            let message = format!("unknown edition `{}` (expected one of: {})", edition, EDITIONS.join(", "));
+            self.error(format!("{}/edition", location), message);
+        }
+        let valid_opt_level = match &exercise.opt_level {
+            Some(OptLevel::Level(level)) => *level <= 3,
+            Some(OptLevel::Named(name)) => name == "s" || name == "z",
+            None => true,
+        };
+        if !valid_opt_level {
+            self.error(format!("{}/opt_level", location), "expected 0, 1, 2, 3, \"s\" or \"z\"");
+        }
+
+        if let ClippyPolicy::Deduct(n) = exercise.clippy {
+            self.positive(&format!("{}/clippy", location), "deduct", Some(n.into()));
+        }
+        if let Some(FormatPolicy::Deduct(n)) = exercise.format_check {
+            self.positive(&format!("{}/format_check", location), "deduct", Some(n.into()));
+        }
+
+        if exercise.float_tolerance.is_some_and(|t| t <= 0.0) {
+            self.error(format!("{}/float_tolerance", location), "expected a positive number");
+        }
+
+        if let Some(hint) = &exercise.hint {
+            self.non_empty(location, "hint", hint);
+        }
+        for (index, hint) in exercise.hints.iter().enumerate() {
+            self.non_empty(&format!("{}/hints", location), &index.to_string(), hint);
+        }
+
+        score
+    }
+
+    // 参考实现可能只在评测环境中提供，本地缺失时只给出警告
+    fn check_differential(&mut self, location: &str, differential: &DifferentialSpec) {
+        let reference = &differential.reference;
+        if !reference.ends_with(".rs") {
+            self.error(format!("{}/reference", location), format!("`{}` is not a .rs file", reference));
+        } else if !differential.reference_path().is_file() {
+            let message = format!("{} does not exist", differential.reference_path().display());
+            self.warning(format!("{}/reference", location), message);
+        }
+        self.positive(location, "cases", Some(differential.cases as i64));
+    }
+
+    fn check_benchmark(&mut self, location: &str, benchmark: &BenchmarkSpec, timeout_secs: u64) {
+        self.positive(location, "budget_ms", Some(benchmark.budget_ms as i64));
+        self.positive(location, "runs", Some(benchmark.runs as i64));
+        if benchmark.tolerance < 0.0 {
+            self.error(format!("{}/tolerance", location), "expected a non-negative number");
+        }
+        // 性能评测计入习题的时间上限，预算内最慢的合格实现也必须能在上限内跑完
+        let worst_secs = benchmark.budget_ms as f64 * benchmark.runs as f64 * (1.0 + benchmark.tolerance) / 1000.0;
+        if worst_secs > timeout_secs as f64 {
+            self.error(
+                location,
+                format!(
+                    "budget_ms * runs * (1 + tolerance) = {:.0} s does not fit in the {} s timeout; raise `timeout_secs`",
+                    worst_secs, timeout_secs
+                ),
+            );
+        }
+        if let Some(test) = &benchmark.test {
+            self.non_empty(location, "test", test);
+        }
+        if let Some(bench) = &benchmark.bench {
+            self.non_empty(location, "bench", bench);
+        }
+        if benchmark.test.is_some() && benchmark.bench.is_some() {
+            self.error(location, "`test` and `bench` are mutually exclusive");
+        }
+    }
+
+    fn check_path(&mut self, location: &str, path: &str, exercise_type: Option<&str>) {
+        if self.created.contains(&path) {
+            return;
+        }
+        let full_path = self.exercises_root.join(path);
+        if !full_path.exists() {
+            let message = format!("`{}` does not exist under {}/", path, self.exercises_root.display());
+            self.error(location, message);
+            return;
+        }
+        match exercise_type {
+            Some("single_file") if !(full_path.is_file() && path.ends_with(".rs")) => {
+                self.error(location, format!("`{}` is not a .rs file", path));
+            }
+            Some("cargo_project" | "io_judge") if !full_path.join("Cargo.toml").is_file() => {
+                self.error(location, format!("`{}` is not a cargo project (no Cargo.toml)", path));
+            }
+            Some("io_judge") if !full_path.join(CASES_DIR).is_dir() => {
+                self.error(location, format!("`{}` has no {}/ directory of test cases", path, CASES_DIR));
+            }
+            _ => {}
+        }
+    }
+
+    // 磁盘上存在但未登记在配置中的习题（exercises/<难度>/ 下的 .rs 文件或 cargo 项目）
+    fn check_unlisted(&mut self, tiers: &[String]) {
+        for tier in tiers {
+            let Ok(entries) = fs::read_dir(self.exercises_root.join(tier)) else {
+                continue;
+            };
+            let mut unlisted: Vec<String> = entries
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    let path = entry.path();
+                    path.join("Cargo.toml").is_file() || path.extension().is_some_and(|ext| ext == "rs")
+                })
+                .map(|entry| format!("{}/{}", tier, entry.file_name().to_string_lossy()))
+                .filter(|path| !self.paths.contains(path))
+                .collect();
+            unlisted.sort();
+
+            for path in unlisted {
+                let location = self.exercises_root.join(&path).display().to_string();
+                self.warning(location, "exercise exists on disk but is not listed in the config");
+            }
+        }
+    }
+}
+
+// 校验配置文件，返回发现的全部问题（而不是遇到第一个就停止）
+pub fn validate_config(config_path: &Path, exercises_root: &Path) -> Vec<Problem> {
+    let location = config_path.display().to_string();
+    match fs::read_to_string(config_path) {
+        Ok(content) => validate_source(&location, &content, exercises_root),
+        Err(e) => vec![Problem { severity: Severity::Error, location, message: format!("cannot read file: {}", e) }],
+    }
+}
+
+// 校验配置文件的内容，`location` 为文件名，用于报告 JSON 语法错误的位置
+fn validate_source(location: &str, content: &str, exercises_root: &Path) -> Vec<Problem> {
+    match serde_json::from_str(content) {
+        Ok(root) => validate_value(&root, exercises_root, &[]),
+        Err(e) => vec![Problem {
+            severity: Severity::Error,
+            location: format!("{}:{}:{}", location, e.line(), e.column()),
+            message: format!("invalid JSON: {}", e),
+        }],
+    }
+}
+
+// 校验已解析的配置；`created` 中的习题路径视为已存在（`new` 在生成习题文件之前检查修改后的配置）
+pub fn validate_value(root: &Value, exercises_root: &Path, created: &[&str]) -> Vec<Problem> {
+    let mut validator =
+        Validator { exercises_root, created, problems: Vec::new(), names: HashMap::new(), paths: Vec::new() };
+    let Some(tiers) = root.as_object() else {
+        validator.error("/", "expected an object mapping difficulty names to exercise lists");
+        return validator.problems;
+    };
+
+    if tiers.is_empty() {
+        validator.error("/", "no difficulty tiers defined");
+    }
+    for (tier, value) in tiers {
+        if tier.trim().is_empty() {
+            validator.error("/", "tier names must not be empty");
+        }
+        validator.check_tier(tier, value);
+    }
+
+    let tier_names: Vec<String> = tiers.keys().cloned().collect();
+    validator.check_unlisted(&tier_names);
+    validator.problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EXERCISES_DIR;
+
+    // (配置, 期望依次报告的问题的开头)；以仓库中的 exercises/ 为习题目录，
+    // 习题登记在 extra 这类磁盘上没有对应目录的难度中时不会报告未登记的习题
+    const CASES: &[(&str, &[&str])] = &[
+        (
+            r#"{"extra": {"expected_total": 3, "exercises": [
+                {"name": "a", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1},
+                {"name": "b", "path": "easy/algorithm2.rs", "type": "single_file", "score": 2}
+            ]}}"#,
+            &[],
+        ),
+        (
+            r#"{"extra": {"expected_total": 2, "exercises": [
+                {"name": "a", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1},
+                {"name": "a", "path": "easy/algorithm2.rs", "type": "single_file", "score": 1}
+            ]}}"#,
+            &["error: /extra/exercises/1/name: duplicate exercise name `a` (first defined at /extra/exercises/0/name)"],
+        ),
+        (
+            r#"{
+                "hard": {"default_score": 1, "exercises": [
+                    {"name": "t1", "path": "hard/solutiont1", "type": "cargo_project"},
+                    {"name": "t2", "path": "hard/solutiont2", "type": "cargo_project"},
+                    {"name": "t3", "path": "hard/solutiont3", "type": "cargo_project"},
+                    {"name": "t4", "path": "hard/solutiont4", "type": "cargo_project"},
+                    {"name": "t5", "path": "hard/solutiont5", "type": "cargo_project"}
+                ]},
+                "extra": {"expected_total": 5, "default_score": 2, "exercises": [
+                    {"name": "b", "path": "easy/algorithm2.rs", "type": "single_file"}
+                ]}
+            }"#,
+            &[
+                "error: /hard: scores add up to 5, but the README specifies 50 for this tier",
+                "error: /extra: scores add up to 2, but expected_total specifies 5 for this tier",
+            ],
+        ),
+        (
+            r#"{"extra": [{"name": "a", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1, "timeout_sec": 5}]}"#,
+            &["error: /extra/0: unknown field `timeout_sec`, expected one of"],
+        ),
+        (
+            r#"{"extra": [{"name": "a", "path": "easy/missing.rs", "type": "script", "score": 0}]}"#,
+            &[
+                "error: /extra/0/type: unknown type `script`",
+                "error: /extra/0/path: `easy/missing.rs` does not exist under exercises/",
+                "error: /extra/0/score: expected a positive integer",
+            ],
+        ),
+        (
+            r#"{"extra": [{"name": "a", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1, "clippy": "loud"}]}"#,
+            &["error: /extra/0: unknown variant `loud`"],
+        ),
+        (
+            r#"{"extra": {"colour": "green", "exercises": []}}"#,
+            &["error: /extra: unknown field `colour`"],
+        ),
+        (
+            r#"{"extra": {"expected_total": 3, "exercises": [
+                {"name": "a", "path": "easy/algorithm1.rs", "type": "single_file", "score": 1,
+                 "benchmark": {"budget_ms": 30000}},
+                {"name": "b", "path": "easy/algorithm2.rs", "type": "single_file", "score": 2, "timeout_secs": 200,
+                 "benchmark": {"budget_ms": 30000}}
+            ]}}"#,
+            &["error: /extra/exercises/0/benchmark: budget_ms * runs * (1 + tolerance) = 165 s does not fit in the 120 s timeout"],
+        ),
+        (
+            r#"{"extra": [{"name": "a", "path": "normal/solution1", "type": "cargo_project", "score": 1, "edition": "2021"}]}"#,
+            &["error: /extra/0/edition: only supported for single_file exercises"],
+        ),
+        (
+            r#"{"normal": {"expected_total": 1, "exercises": [
+                {"name": "a", "path": "normal/solution1", "type": "cargo_project", "score": 1}
+            ]}}"#,
+            &[
+                "warning: exercises/normal/solution2: exercise exists on disk but is not listed",
+                "warning: exercises/normal/solution3: exercise exists on disk but is not listed",
+                "warning: exercises/normal/solution4: exercise exists on disk but is not listed",
+                "warning: exercises/normal/solution5: exercise exists on disk but is not listed",
+            ],
+        ),
+        (r#"{"easy": [}"#, &["error: exercise_config.json:1:11: invalid JSON"]),
+    ];
+
+    #[test]
+    fn reports_the_expected_problems() {
+        for (config, expected) in CASES {
+            let problems = validate_source("exercise_config.json", config, Path::new(EXERCISES_DIR));
+            let problems: Vec<String> = problems.iter().map(Problem::to_string).collect();
+            let matches = problems.len() == expected.len()
+                && problems.iter().zip(expected.iter()).all(|(problem, prefix)| problem.starts_with(prefix));
+            assert!(matches, "config {}\nexpected {:?}\ngot {:#?}", config, expected, problems);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use super::hints::exercise_hints;
+use super::{evaluate_exercise, Evaluation, Exercise, ExerciseStatus, OutputBuffer, Settings};
+use inotify::{Inotify, WatchMask};
+use std::ffi::{OsStr, OsString};
+use std::io::{self, BufRead, ErrorKind};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use std::fs;
+
+// 轮询文件变化与键盘输入的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// 编辑器保存时往往连续产生多个事件，收到第一个事件后再等待这么久合并为一次重新评测
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+// 同一道习题每连续失败这么多次，给出下一条提示
+const FAILURES_PER_HINT: usize = 3;
+
+enum WatchEvent {
+    Changed,
+    Quit,
+}
+
+// 监听一道习题的源码：单文件习题监听所在目录并按文件名过滤（兼容先写临时文件再重命名的编辑器），
+// cargo 项目监听项目目录及除 target 外的所有子目录
+struct ExerciseWatcher {
+    inotify: Inotify,
+    file_name: Option<OsString>,
+    buffer: [u8; 4096],
+}
+
+impl ExerciseWatcher {
+    fn new(exercise: &Exercise) -> io::Result<Self> {
+        let path = exercise.full_path();
+        let inotify = Inotify::init()?;
+        let mask = WatchMask::MODIFY
+            | WatchMask::CLOSE_WRITE
+            | WatchMask::CREATE
+            | WatchMask::DELETE
+            | WatchMask::MOVED_TO;
+
+        let file_name = if path.is_dir() {
+            add_watches_recursively(&inotify, &path, mask)?;
+            None
+        } else {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            inotify.watches().add(dir, mask)?;
+            path.file_name().map(OsStr::to_os_string)
+        };
+
+        Ok(ExerciseWatcher { inotify, file_name, buffer: [0; 4096] })
+    }
+
+    // 非阻塞地读取已到达的事件，返回其中是否有与习题相关的改动
+    fn poll(&mut self) -> io::Result<bool> {
+        let events = match self.inotify.read_events(&mut self.buffer) {
+            Ok(events) => events,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(false),
+            Err(e) => return Err(e),
+        };
+
+        let mut changed = false;
+        for event in events {
+            let Some(name) = event.name else {
+                continue;
+            };
+            changed |= match &self.file_name {
+                Some(file_name) => name == file_name,
+                None => is_relevant(name),
+            };
+        }
+        Ok(changed)
+    }
+}
+
+fn add_watches_recursively(inotify: &Inotify, dir: &Path, mask: WatchMask) -> io::Result<()> {
+    inotify.watches().add(dir, mask)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && is_relevant(&entry.file_name()) {
+            add_watches_recursively(inotify, &entry.path(), mask)?;
+        }
+    }
+    Ok(())
+}
+
+// 忽略构建产物、隐藏文件与编辑器的备份文件
+fn is_relevant(name: &OsStr) -> bool {
+    let name = name.to_string_lossy();
+    !(name == "target" || name == "Cargo.lock" || name.starts_with('.') || name.ends_with('~'))
+}
+
+// 在后台读取标准输入，输入 q 回车即退出 watch 模式
+fn spawn_stdin_reader() -> Receiver<WatchEvent> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().eq_ignore_ascii_case("q") && tx.send(WatchEvent::Quit).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn wait_for_change(watcher: &mut ExerciseWatcher, keys: &Receiver<WatchEvent>) -> io::Result<WatchEvent> {
+    loop {
+        match keys.try_recv() {
+            Ok(event) => return Ok(event),
+            Err(TryRecvError::Empty | TryRecvError::Disconnected) => {}
+        }
+        if watcher.poll()? {
+            thread::sleep(DEBOUNCE);
+            watcher.poll()?;
+            return Ok(WatchEvent::Changed);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn clear_screen() {
+    print!("{}{}", termion::clear::All, termion::cursor::Goto(1, 1));
+}
+
+// 每次重新评测前都重新检查官方测试，watch 期间修改测试同样记为 tests_modified
+fn evaluate_and_show(exercise: &Exercise, settings: &Settings, done: usize, total: usize) -> Evaluation {
+    let evaluation = settings.check_tests(exercise).unwrap_or_else(|| {
+        let mut out = OutputBuffer::default();
+        let evaluation = evaluate_exercise(exercise, settings, &mut out);
+        out.flush();
+        evaluation
+    });
+    print_progress(done + usize::from(evaluation.status == ExerciseStatus::Pass), total);
+    evaluation
+}
+
+fn print_progress(done: usize, total: usize) {
+    const WIDTH: usize = 40;
+    let filled = WIDTH * done / total.max(1);
+    println!("\nProgress: [{}{}] {}/{}", "#".repeat(filled), "-".repeat(WIDTH - filled), done, total);
+}
+
+// rustlings 风格的 watch 模式：按顺序评测，停在第一道未通过的习题上，
+// 监听其源码改动并自动重新评测，反复失败时逐条给出提示，通过后继续下一题；输入 q 回车退出
+pub fn watch_exercises(exercises: &[Exercise], settings: &Settings) -> Vec<Evaluation> {
+    let keys = spawn_stdin_reader();
+    let mut results = Vec::new();
+
+    for (index, exercise) in exercises.iter().enumerate() {
+        let mut evaluation = evaluate_and_show(exercise, settings, index, exercises.len());
+
+        if evaluation.status != ExerciseStatus::Pass {
+            let mut watcher = match ExerciseWatcher::new(exercise) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    eprintln!("Failed to watch {}: {}", exercise.full_path().display(), e);
+                    results.push(evaluation);
+                    return results;
+                }
+            };
+
+            let hints = exercise_hints(exercise);
+            let mut failures = 0;
+            let mut shown_hints = 0;
+            while evaluation.status != ExerciseStatus::Pass {
+                // 仅差删除未完成标记的习题不算失败
+                if evaluation.status != ExerciseStatus::Pending {
+                    failures += 1;
+                }
+                if failures >= FAILURES_PER_HINT * (shown_hints + 1) && shown_hints < hints.len() {
+                    shown_hints += 1;
+                }
+                // 每次重新评测都会清屏，因此重复显示最近给出的提示
+                if shown_hints > 0 {
+                    println!("\nHint {}/{}: {}", shown_hints, hints.len(), hints[shown_hints - 1]);
+                }
+                println!(
+                    "Watching {} for changes... (enter 'q' to quit)",
+                    exercise.full_path().display()
+                );
+                match wait_for_change(&mut watcher, &keys) {
+                    Ok(WatchEvent::Changed) => {
+                        clear_screen();
+                        evaluation = evaluate_and_show(exercise, settings, index, exercises.len());
+                    }
+                    Ok(WatchEvent::Quit) => {
+                        results.push(evaluation);
+                        return results;
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to watch {}: {}", exercise.full_path().display(), e);
+                        results.push(evaluation);
+                        return results;
+                    }
+                }
+            }
+        }
+
+        results.push(evaluation);
+    }
+
+    results
+}
@@ -0,0 +1,4 @@
+pub fn goldbach_conjecture() -> String {
+    // TODO: 这里写逻辑
+    todo!()
+}
@@ -0,0 +1,4 @@
+pub fn find_max_prime_factor(number: u128) -> u128 {
+    // TODO: 这里写逻辑
+    todo!()
+}
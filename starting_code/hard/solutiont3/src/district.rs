@@ -0,0 +1,8 @@
+/// 计算省份数量
+///
+/// # 返回值
+/// 返回格式化的省份数量信息
+pub fn count_provinces() -> String {
+    // TODO: 这里写逻辑
+    todo!()
+}
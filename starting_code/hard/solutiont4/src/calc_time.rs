@@ -0,0 +1,5 @@
+// 处理时间信息并返回格式化结果
+pub fn time_info(time: &str) -> String {
+    // TODO: 这里写逻辑
+    todo!()
+}
@@ -0,0 +1,17 @@
+/// 公开退休计算接口
+///
+/// # 参数
+/// * `time` - 出生日期字符串，格式为"YYYY-MM"
+/// * `tp` - 人员类型，如"男职工"
+///
+/// # 返回值
+/// 返回格式化的退休信息："退休年月,工龄,延迟月数"
+///
+/// # 处理流程
+/// 1. 解析日期字符串
+/// 2. 匹配人员类型获取政策参数
+/// 3. 调用核心计算函数
+pub fn retire_time(time: &str, tp: &str) -> String {
+    // TODO: 这里写逻辑
+    todo!()
+}
@@ -0,0 +1,4 @@
+pub fn new_count_distinct(input_str: &str) -> usize {
+    // TODO: 这里写逻辑
+    todo!()
+}
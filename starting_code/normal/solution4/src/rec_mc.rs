@@ -0,0 +1,4 @@
+pub fn dp_rec_mc(amount: u32) -> u32 {
+    // TODO: 这里写逻辑
+    todo!()
+}
@@ -0,0 +1,4 @@
+pub fn odd_fibnacci_sum(threshold: u32) -> u32 {
+    // TODO: 这里写逻辑
+    todo!()
+}
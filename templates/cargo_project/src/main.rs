@@ -0,0 +1,7 @@
+// I AM NOT DONE
+mod {{name}};
+
+fn main() {
+    let result = {{name}}::solve(1);
+    println!("result: {result}");
+}
@@ -0,0 +1,3 @@
+pub fn solve(input: i32) -> i32 {
+    todo!("implement {{name}} for {}", input)
+}
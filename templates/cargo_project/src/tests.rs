@@ -0,0 +1,25 @@
+// src/tests.rs
+mod {{name}};
+
+#[cfg(test)]
+mod tests {
+    use super::{{name}}::solve;
+    // 定义测试用例和预期结果
+    const TEST_CASES: &[(i32, i32)] = &[
+        (1, 1),
+    ];
+    // 定义一个测试函数来验证每个测试用例
+    #[test]
+    fn test_{{name}}() {
+        let mut passed = 0;
+        for (input, expected) in TEST_CASES {
+            let result = solve(*input);
+            if result == *expected {
+                passed += 1;
+            }
+        }
+        let total_score = passed as f64 * 100.0 / TEST_CASES.len() as f64;
+        println!("Total score: {:.2}", total_score);
+        assert_eq!(passed, TEST_CASES.len());
+    }
+}
@@ -0,0 +1,184 @@
+// 差分测试框架，由评测器与参考实现拼接成一个测试模块，和学生代码一起编译。
+// 参考实现文件中定义 `struct Case` 并为其实现 `Differential`；这里不写 `use`，标准库路径一律写全，
+// 以免与参考实现自己的 `use` 冲突。
+#![allow(dead_code)]
+
+// 生成输入的规模上限，规模随用例序号从 0 线性增长到这里
+pub const MAX_SIZE: usize = 64;
+
+// 缩小失败输入时最多尝试的候选数
+const MAX_SHRINK_STEPS: usize = 10_000;
+
+// 失败信息中单个值的最大长度
+const MAX_SHOWN_CHARS: usize = 300;
+
+pub trait Differential {
+    type Input: Clone + ::std::fmt::Debug;
+    type Output: PartialEq + ::std::fmt::Debug;
+
+    // 按规模 size 生成一个随机输入
+    fn generate(rng: &mut Rng, size: usize) -> Self::Input;
+
+    fn reference(input: &Self::Input) -> Self::Output;
+
+    fn student(input: &Self::Input) -> Self::Output;
+
+    // 比 input 更小的候选输入，按优先程度排列；默认不缩小
+    fn shrink(_input: &Self::Input) -> Vec<Self::Input> {
+        Vec::new()
+    }
+}
+
+// splitmix64，同一种子在任何平台上生成相同的序列
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // [0, n) 中的整数，n 为 0 时返回 0
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    // [low, high] 中的整数
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        let span = high.wrapping_sub(low) as u64;
+        match span.checked_add(1) {
+            Some(n) => low.wrapping_add(self.below(n) as i64),
+            None => self.next_u64() as i64,
+        }
+    }
+
+    // 以 1/n 的概率返回 true
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+// 向量的缩小候选：保留前半或后半，再逐个删除元素
+pub fn shrink_vec<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    if items.len() > 1 {
+        candidates.push(items[..items.len() / 2].to_vec());
+        candidates.push(items[items.len() / 2..].to_vec());
+    }
+    for i in 0..items.len() {
+        let mut smaller = items.to_vec();
+        smaller.remove(i);
+        candidates.push(smaller);
+    }
+    candidates
+}
+
+// 整数的缩小候选：0、减半以及逐步向 0 靠近
+pub fn shrink_int(value: i64) -> Vec<i64> {
+    let mut candidates = Vec::new();
+    let mut distance = value / 2;
+    if value != 0 {
+        candidates.push(0);
+    }
+    while distance != 0 {
+        candidates.push(value - distance);
+        distance /= 2;
+    }
+    candidates
+}
+
+// 一个输入上学生实现与参考实现的差异，None 表示输出一致
+fn compare<T: Differential>(input: &T::Input) -> Option<String> {
+    let expected = match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| T::reference(input))) {
+        Ok(expected) => expected,
+        Err(payload) => return Some(format!("reference implementation panicked: {}", panic_message(&*payload))),
+    };
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| T::student(input))) {
+        Ok(actual) if actual == expected => None,
+        Ok(actual) => Some(format!("expected: {}\ngot: {}", show(&expected), show(&actual))),
+        Err(payload) => Some(format!("expected: {}\npanicked: {}", show(&expected), panic_message(&*payload))),
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(non-string panic payload)".to_string()
+    }
+}
+
+fn show<V: ::std::fmt::Debug>(value: &V) -> String {
+    let shown = format!("{:?}", value);
+    match shown.char_indices().nth(MAX_SHOWN_CHARS) {
+        Some((end, _)) => format!("{}… ({} chars)", &shown[..end], shown.chars().count()),
+        None => shown,
+    }
+}
+
+// 反复换成仍然失败的第一个更小候选，直到没有候选失败或达到尝试上限
+fn shrink<T: Differential>(mut input: T::Input, mut failure: String) -> (T::Input, String) {
+    let mut steps = 0;
+    'outer: while steps < MAX_SHRINK_STEPS {
+        for candidate in T::shrink(&input) {
+            steps += 1;
+            if let Some(candidate_failure) = compare::<T>(&candidate) {
+                input = candidate;
+                failure = candidate_failure;
+                continue 'outer;
+            }
+            if steps >= MAX_SHRINK_STEPS {
+                break;
+            }
+        }
+        break;
+    }
+    (input, failure)
+}
+
+// 用固定种子生成 cases 个输入逐一比较，发现不一致时缩小输入并以最小的失败输入使测试失败
+pub fn run<T: Differential>(seed: u64, cases: usize) {
+    // 学生代码的 panic 由这里捕获并报告，静默默认的 panic 输出
+    let hook = ::std::panic::take_hook();
+    ::std::panic::set_hook(Box::new(|_| {}));
+
+    let mut rng = Rng::new(seed);
+    let mut failure = None;
+    for case in 0..cases {
+        let size = case * MAX_SIZE / cases.max(1);
+        let input = T::generate(&mut rng, size);
+        if let Some(difference) = compare::<T>(&input) {
+            failure = Some((case + 1, shrink::<T>(input, difference)));
+            break;
+        }
+    }
+
+    ::std::panic::set_hook(hook);
+    if let Some((case, (input, difference))) = failure {
+        panic!(
+            "differential test failed on case {} of {} (seed {})\nsmallest failing input: {}\n{}",
+            case,
+            cases,
+            seed,
+            show(&input),
+            difference
+        );
+    }
+    println!("differential test passed {} cases (seed {})", cases, seed);
+}
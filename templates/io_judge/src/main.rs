@@ -0,0 +1,17 @@
+// I AM NOT DONE
+/*
+    {{name}}
+    Describe the problem here: the input read from stdin and the output expected on stdout.
+    The test cases are the pairs of `.in` / `.out` files under cases/.
+
+    Hint: Describe a hint here.
+*/
+
+use std::io::{self, Read};
+
+fn main() {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let numbers: Vec<i64> = input.split_whitespace().map(|token| token.parse().unwrap()).collect();
+    todo!("implement {{name}} for {:?}", numbers)
+}
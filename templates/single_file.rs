@@ -0,0 +1,23 @@
+// I AM NOT DONE
+/*
+    {{name}}
+    Describe the problem here.
+
+    Hint: Describe a hint here.
+*/
+
+pub fn solve(input: i32) -> i32 {
+    todo!("implement {{name}} for {}", input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_{{name}}_1() {
+        let result = solve(1);
+        println!("solve(1) = {}", result);
+        assert_eq!(result, 1);
+    }
+}